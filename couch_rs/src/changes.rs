@@ -99,7 +99,7 @@ impl ChangesStream {
 
 async fn get_changes(client: Client, database: String, params: HashMap<String, String>) -> CouchResult<Response> {
     let path = format!("{database}/_changes");
-    let res = client.req(Method::GET, &path, Some(&params)).send().await?;
+    let res = client.send(client.req(Method::GET, &path, Some(&params))).await?;
     Ok(res)
 }
 
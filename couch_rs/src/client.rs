@@ -1,20 +1,38 @@
 use crate::{
+    auth::{AuthContext, Authenticator, BasicAuth, CookieAuth},
     database::Database,
     error::{CouchError, CouchResult},
     management::{ClusterSetup, ClusterSetupGetResponse, EnsureDbsExist, Membership},
-    types::system::{CouchResponse, CouchStatus, DbInfo},
+    retry::{RetryEvent, RetryPolicy},
+    types::{
+        document::DocumentCreatedResult,
+        options::CreateDbOptions,
+        replication::{
+            is_replication_document, ReplicationDocument, ReplicationEndpoint, ReplicationOptions, ReplicationRequest,
+            ReplicationResult,
+        },
+        system::{CouchResponse, CouchStatus, DbInfo},
+        tasks::{ActiveTask, SchedulerDoc, SchedulerDocs, SchedulerJobs},
+        user::{user_id, CouchUser},
+    },
 };
-use base64::engine::general_purpose;
 use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
 use reqwest::{
-    header::{self, HeaderMap, HeaderValue, CONTENT_TYPE, REFERER, USER_AGENT},
-    Method, RequestBuilder, StatusCode, Url,
+    header::{HeaderMap, HeaderValue, CONTENT_TYPE, REFERER, USER_AGENT},
+    Method, Request, RequestBuilder, Response, StatusCode, Url,
 };
-use std::{collections::HashMap, io::Write, time::Duration};
+use serde_json::Value;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+/// The default `User-Agent` header: `couch_rs/<version>`
+const DEFAULT_USER_AGENT: &str = concat!("couch_rs/", env!("CARGO_PKG_VERSION"));
 
 fn construct_json_headers(uri: Option<&str>) -> HeaderMap {
     let mut headers = HeaderMap::new();
-    headers.insert(USER_AGENT, HeaderValue::from_static("reqwest"));
     headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
 
     if let Some(u) = uri {
@@ -30,16 +48,51 @@ fn parse_server(uri: &str) -> CouchResult<Url> {
     Ok(parsed_url)
 }
 
-pub(crate) async fn is_accepted(request: RequestBuilder) -> bool {
-    if let Ok(res) = request.send().await {
+/// The URI of `path` on the server, keeping the path of the server URI, like a `/couchdb/` reverse proxy prefix.
+pub(crate) fn server_uri(server: &Url, path: &str) -> Url {
+    let mut uri = server.clone();
+    let base = server.path().trim_end_matches('/');
+    uri.set_path(&format!("{}/{}", base, path.trim_start_matches('/')));
+    uri
+}
+
+/// Sleeps for `poll_interval` before polling again; fails with a `REQUEST_TIMEOUT` error once `deadline` has passed.
+pub(crate) async fn wait_until_deadline(
+    poll_interval: Duration,
+    deadline: Instant,
+    waiting_for: &str,
+) -> CouchResult<()> {
+    if Instant::now() >= deadline {
+        return Err(CouchError::new(
+            format!("timed out waiting for {waiting_for}"),
+            StatusCode::REQUEST_TIMEOUT,
+        ));
+    }
+    tokio::time::sleep(poll_interval.min(deadline - Instant::now())).await;
+    Ok(())
+}
+
+fn paging_params(limit: Option<u64>, skip: Option<u64>) -> HashMap<String, String> {
+    let mut params = HashMap::new();
+    if let Some(limit) = limit {
+        params.insert(s!("limit"), limit.to_string());
+    }
+    if let Some(skip) = skip {
+        params.insert(s!("skip"), skip.to_string());
+    }
+    params
+}
+
+pub(crate) async fn is_accepted(client: &Client, request: RequestBuilder) -> bool {
+    if let Ok(res) = client.send(request).await {
         res.status() == StatusCode::ACCEPTED
     } else {
         false
     }
 }
 
-pub(crate) async fn is_ok(request: RequestBuilder) -> bool {
-    if let Ok(res) = request.send().await {
+pub(crate) async fn is_ok(client: &Client, request: RequestBuilder) -> bool {
+    if let Ok(res) = client.send(request).await {
         let status = res.status();
         status.is_success() || status == StatusCode::NOT_MODIFIED
     } else {
@@ -52,9 +105,11 @@ pub(crate) async fn is_ok(request: RequestBuilder) -> bool {
 #[derive(Debug, Clone)]
 pub struct Client {
     client: reqwest::Client,
-    _gzip: bool,
-    _timeout: Option<u64>,
+    timeout: Option<Duration>,
+    default_headers: HeaderMap,
     uri: Url,
+    auth: Option<Arc<dyn Authenticator>>,
+    retry: Option<RetryPolicy>,
     pub db_prefix: String,
 }
 
@@ -62,6 +117,8 @@ const TEST_DB_HOST: &str = "http://localhost:5984";
 const TEST_DB_USER: &str = "admin";
 const TEST_DB_PW: &str = "password";
 const DEFAULT_TIME_OUT: u64 = 10;
+const REPLICATOR_DB: &str = "_replicator";
+const USERS_DB: &str = "_users";
 
 impl Client {
     /// new creates a new Couch client with a default timeout of 10 seconds.
@@ -103,36 +160,49 @@ impl Client {
         password: Option<&str>,
         timeout: Option<u64>,
     ) -> CouchResult<Client> {
-        let mut headers = HeaderMap::new();
-
+        let mut builder = ClientBuilder::new(uri);
+        builder = match timeout {
+            Some(t) => builder.timeout(Duration::from_secs(t)),
+            None => builder.no_timeout(),
+        };
         if let Some(username) = username {
-            let mut header_value = b"Basic ".to_vec();
-            {
-                let mut encoder = base64::write::EncoderWriter::new(&mut header_value, &general_purpose::STANDARD);
-                // The unwraps here are fine because Vec::write* is infallible.
-                write!(encoder, "{username}:").unwrap();
-                if let Some(password) = password {
-                    write!(encoder, "{password}").unwrap();
-                }
-            }
-
-            let auth_header = HeaderValue::from_bytes(&header_value).expect("can not set AUTHORIZATION header");
-            headers.insert(header::AUTHORIZATION, auth_header);
+            builder = builder.auth(BasicAuth::new(username, password));
         }
+        builder.build()
+    }
 
-        let mut client_builder = reqwest::Client::builder().default_headers(headers).gzip(true);
-        if let Some(t) = timeout {
-            client_builder = client_builder.timeout(Duration::new(t, 0));
-        }
-        let client = client_builder.build()?;
+    /// `builder` creates a [`ClientBuilder`], to configure the authentication and the HTTP transport of a
+    /// new Couch client. The URI has to be in this format: <http://hostname:5984>
+    pub fn builder(uri: &str) -> ClientBuilder {
+        ClientBuilder::new(uri)
+    }
 
-        Ok(Client {
-            client,
-            uri: parse_server(uri)?,
-            _gzip: true,
-            _timeout: timeout,
-            db_prefix: String::new(),
-        })
+    /// `new_with_session` creates a new Couch client with a default timeout of 10 seconds, that uses cookie
+    /// authentication through the `_session` endpoint instead of sending the credentials with every request.
+    /// The session is started on the first request, and renewed automatically. See [`CookieAuth`] for details.
+    /// The URI has to be in this format: <http://hostname:5984>, for example: <http://192.168.64.5:5984>
+    pub fn new_with_session(uri: &str, username: &str, password: &str) -> CouchResult<Client> {
+        Client::new_with_auth(uri, CookieAuth::new(username, password))
+    }
+
+    /// `new_with_auth` creates a new Couch client with a default timeout of 10 seconds, using the given
+    /// authentication strategy; one of the strategies in [`auth`](crate::auth), or a custom [`Authenticator`].
+    /// The URI has to be in this format: <http://hostname:5984>, for example: <http://192.168.64.5:5984>
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::{auth::JwtAuth, error::CouchResult, Client};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let auth = JwtAuth::new(|| std::env::var("COUCHDB_JWT").unwrap_or_default());
+    ///     let client = Client::new_with_auth("http://localhost:5984", auth)?;
+    ///     let dbs = client.list_dbs().await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn new_with_auth(uri: &str, auth: impl Authenticator + 'static) -> CouchResult<Client> {
+        ClientBuilder::new(uri).auth(auth).build()
     }
 
     pub fn get_self(&mut self) -> &mut Self {
@@ -170,7 +240,7 @@ impl Client {
     /// }
     ///```
     pub async fn list_dbs(&self) -> CouchResult<Vec<String>> {
-        let response = self.get("/_all_dbs", None).send().await?;
+        let response = self.send(self.get("/_all_dbs", None)).await?.error_for_status()?;
         let data = response.json().await?;
 
         Ok(data)
@@ -183,33 +253,109 @@ impl Client {
     }
 
     /// Connect to an existing database, or create a new one, when this one does not exist.
+    /// Other failures, like missing permissions, are returned as errors instead of creating the database.
     pub async fn db(&self, dbname: &str) -> CouchResult<Database> {
-        let name = self.build_dbname(dbname);
+        match self.open_db(dbname).await {
+            Err(err) if err.is_not_found() => self.make_db(dbname).await,
+            result => result,
+        }
+    }
 
-        let db = Database::new(name.clone(), self.clone());
+    /// Connect to an existing database; fails with a `NOT_FOUND` error when it does not exist.
+    pub async fn open_db(&self, dbname: &str) -> CouchResult<Database> {
+        let name = self.build_dbname(dbname);
 
         let head_response = self
-            .head(&name, None)
-            .headers(construct_json_headers(None))
-            .send()
+            .send(self.head(&name, None).headers(construct_json_headers(None)))
             .await?;
 
-        match head_response.status() {
-            StatusCode::OK => Ok(db),
-            _ => self.make_db(dbname).await,
+        let status = head_response.status();
+        if status.is_success() {
+            Ok(Database::new(name, self.clone()))
+        } else {
+            Err(CouchError::new(format!("unable to open database {dbname}"), status))
         }
     }
 
-    /// Create a new database with the given name
+    /// Create a new database with the given name, or connect to it when it already exists
     pub async fn make_db(&self, dbname: &str) -> CouchResult<Database> {
+        self.make_db_with_options(dbname, &CreateDbOptions::default()).await
+    }
+
+    /// Create a new database with the given name and options, or connect to it when it already exists. An existing
+    /// database must have the requested partitioning, shards and replicas, otherwise this fails with a
+    /// `PRECONDITION_FAILED` error. For example a partitioned database:
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::document::DocumentCollection;
+    /// use couch_rs::error::CouchResult;
+    /// use couch_rs::types::options::CreateDbOptions;
+    /// use serde_json::Value;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client
+    ///         .make_db_with_options("sensors", &CreateDbOptions::default().partitioned(true))
+    ///         .await?;
+    ///     let readings: DocumentCollection<Value> = db.partition("sensor-1")?.all_docs(None).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn make_db_with_options(&self, dbname: &str, options: &CreateDbOptions) -> CouchResult<Database> {
+        match self.create_db_with(dbname, options).await {
+            // created concurrently, or it already existed
+            Err(err) if err.status() == Some(StatusCode::PRECONDITION_FAILED) => {
+                if *options != CreateDbOptions::default() {
+                    let info = self.get_info(dbname).await?;
+                    let matches = info.props.partitioned.unwrap_or(false) == options.partitioned
+                        && options.q.is_none_or(|q| q == info.cluster.q)
+                        && options.n.is_none_or(|n| n == info.cluster.n);
+                    if !matches {
+                        return Err(CouchError::new(
+                            format!("database {dbname} already exists with other options"),
+                            StatusCode::PRECONDITION_FAILED,
+                        ));
+                    }
+                }
+                Ok(Database::new(self.build_dbname(dbname), self.clone()))
+            }
+            result => result,
+        }
+    }
+
+    /// Create a new database with the given name and options; fails with a `PRECONDITION_FAILED` error when the
+    /// database already exists.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::error::CouchResult;
+    /// use couch_rs::types::options::CreateDbOptions;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let options = CreateDbOptions {
+    ///         q: Some(8),
+    ///         n: Some(3),
+    ///         partitioned: false,
+    ///     };
+    ///     let db = client.create_db_with("events", &options).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn create_db_with(&self, dbname: &str, options: &CreateDbOptions) -> CouchResult<Database> {
         let name = self.build_dbname(dbname);
 
         let db = Database::new(name.clone(), self.clone());
 
         let put_response = self
-            .put(&name, String::default())
-            .headers(construct_json_headers(None))
-            .send()
+            .send(
+                self.put(&name, String::default())
+                    .query(&options.to_params())
+                    .headers(construct_json_headers(None)),
+            )
             .await?;
 
         let status = put_response.status();
@@ -226,9 +372,10 @@ impl Client {
     /// Destroy the database with the given name
     pub async fn destroy_db(&self, dbname: &str) -> CouchResult<bool> {
         let response = self
-            .delete(&self.build_dbname(dbname), None)
-            .headers(construct_json_headers(None))
-            .send()
+            .send(
+                self.delete(&self.build_dbname(dbname), None)
+                    .headers(construct_json_headers(None)),
+            )
             .await?;
 
         let s: CouchResponse = response.json().await?;
@@ -258,7 +405,7 @@ impl Client {
     /// }
     /// ```
     pub async fn exists(&self, dbname: &str) -> CouchResult<bool> {
-        let result = self.head(&self.build_dbname(dbname), None).send().await?;
+        let result = self.send(self.head(&self.build_dbname(dbname), None)).await?;
         Ok(result.status().is_success())
     }
 
@@ -266,8 +413,7 @@ impl Client {
     /// See [common](https://docs.couchdb.org/en/stable/api/database/common.html) for more details.
     pub async fn get_info(&self, dbname: &str) -> CouchResult<DbInfo> {
         let response = self
-            .get(&self.build_dbname(dbname), None)
-            .send()
+            .send(self.get(&self.build_dbname(dbname), None))
             .await?
             .error_for_status()?;
         let info = response.json().await?;
@@ -278,7 +424,9 @@ impl Client {
     /// including a welcome message and the version of the server.
     /// See [common](https://docs.couchdb.org/en/stable/api/server/common.html) for more details.
     pub async fn check_status(&self) -> CouchResult<CouchStatus> {
-        let response = self.get("", None).headers(construct_json_headers(None)).send().await?;
+        let response = self
+            .send(self.get("", None).headers(construct_json_headers(None)))
+            .await?;
 
         let status = response.json().await?;
         Ok(status)
@@ -287,7 +435,7 @@ impl Client {
     /// Returns membership information about the cluster.
     /// See [_membership](https://docs.couchdb.org/en/latest/api/server/common.html?#membership) for more details.
     pub async fn membership(&self) -> CouchResult<Membership> {
-        let response = self.get("/_membership", None).send().await?;
+        let response = self.send(self.get("/_membership", None)).await?;
         let membership = response.json().await?;
         Ok(membership)
     }
@@ -298,17 +446,241 @@ impl Client {
         let ensure_dbs_array = serde_json::to_value(&request.ensure_dbs_exist)?;
         let ensure_dbs_arrays = serde_json::to_string(&ensure_dbs_array)?;
         let response = self
-            .get("/_cluster_setup", None)
-            .query(&[("ensure_dbs_exist", &ensure_dbs_arrays)])
-            .send()
+            .send(
+                self.get("/_cluster_setup", None)
+                    .query(&[("ensure_dbs_exist", &ensure_dbs_arrays)]),
+            )
             .await?;
         let response: ClusterSetupGetResponse = response.json().await?;
         Ok(response.state)
     }
 
+    /// Replicates the source database to the target database, using `_replicate`. A one-shot replication
+    /// returns when it has completed; a continuous replication returns once it has been started, and keeps
+    /// running until it is cancelled with [`ReplicationOptions::cancel`] or the server restarts.
+    /// Use [`save_replication`](Self::save_replication) for replications that should survive a restart.
+    /// See [_replicate](https://docs.couchdb.org/en/stable/api/server/common.html#replicate) for more details.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::{error::CouchResult, types::replication::ReplicationOptions};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let options = ReplicationOptions::default().create_target(true);
+    ///     let result = client
+    ///         .replicate("http://localhost:5984/a", "http://localhost:5984/b", options)
+    ///         .await?;
+    ///     assert!(result.ok);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn replicate(
+        &self,
+        source: impl Into<ReplicationEndpoint>,
+        target: impl Into<ReplicationEndpoint>,
+        options: ReplicationOptions,
+    ) -> CouchResult<ReplicationResult> {
+        let body = ReplicationRequest {
+            source: &source.into(),
+            target: &target.into(),
+            options: &options,
+        };
+        let response = self
+            .send(self.post("/_replicate", serde_json::to_string(&body)?))
+            .await?;
+
+        let status = response.status();
+        let data: Value = response.json().await?;
+        if !status.is_success() {
+            let s: CouchResponse = serde_json::from_value(data)?;
+            let err = s.reason.or(s.error).unwrap_or_else(|| s!("unspecified error"));
+            return Err(CouchError::new(err, status));
+        }
+
+        Ok(serde_json::from_value(data)?)
+    }
+
+    /// Returns the `_replicator` database, which holds the persistent replications. The database prefix is not applied.
+    #[must_use]
+    pub fn replicator_db(&self) -> Database {
+        Database::new(REPLICATOR_DB.to_string(), self.clone())
+    }
+
+    /// Creates or updates a persistent replication in the `_replicator` database. When the document has no id,
+    /// `CouchDB` generates one. On success the document's id and rev are updated.
+    /// See [replicator database](https://docs.couchdb.org/en/stable/replication/replicator.html) for more details.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::{error::CouchResult, types::replication::{ReplicationDocument, ReplicationOptions}};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let options = ReplicationOptions::default().continuous(true).create_target(true);
+    ///     let mut replication =
+    ///         ReplicationDocument::new("a_to_b", "http://localhost:5984/a", "http://localhost:5984/b", options);
+    ///     client.save_replication(&mut replication).await?;
+    ///     client.delete_replication(&replication).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn save_replication(&self, doc: &mut ReplicationDocument) -> DocumentCreatedResult {
+        let db = self.replicator_db();
+        if doc.id.is_empty() {
+            db.create(doc).await
+        } else {
+            db.save(doc).await
+        }
+    }
+
+    /// Gets a persistent replication from the `_replicator` database, including its state.
+    pub async fn get_replication(&self, id: &str) -> CouchResult<ReplicationDocument> {
+        self.replicator_db().get(id).await
+    }
+
+    /// Lists the persistent replications in the `_replicator` database.
+    pub async fn list_replications(&self) -> CouchResult<Vec<ReplicationDocument>> {
+        let docs = self.replicator_db().get_all_raw().await?;
+        docs.rows
+            .into_iter()
+            .filter(is_replication_document)
+            .map(|doc| Ok(serde_json::from_value(doc)?))
+            .collect()
+    }
+
+    /// Deletes a persistent replication from the `_replicator` database, which stops it when it is running.
+    pub async fn delete_replication(&self, doc: &ReplicationDocument) -> CouchResult<()> {
+        self.replicator_db().remove(doc).await.map(|_| ())
+    }
+
+    /// Returns the `_users` database, which holds the users. The database prefix is not applied.
+    /// Use `typed::Database::<CouchUser>::new("_users".to_string(), client)` for typed access.
+    #[must_use]
+    pub fn users_db(&self) -> Database {
+        Database::new(USERS_DB.to_string(), self.clone())
+    }
+
+    /// Creates a user in the `_users` database. The document id is derived from the user name.
+    /// See [users database](https://docs.couchdb.org/en/stable/intro/security.html#users-documents) for more details.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::{error::CouchResult, types::user::CouchUser};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let mut user = CouchUser::new("jan", "apple", vec!["tenant".to_string()]);
+    ///     client.create_user(&mut user).await?;
+    ///     client.change_password("jan", "orange").await?;
+    ///     client.delete_user(&client.get_user("jan").await?).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn create_user(&self, user: &mut CouchUser) -> DocumentCreatedResult {
+        if user.id.is_empty() {
+            user.id = user_id(&user.name);
+        }
+        self.users_db().save(user).await
+    }
+
+    /// Gets a user by name; the `org.couchdb.user:` prefix is optional.
+    pub async fn get_user(&self, name: &str) -> CouchResult<CouchUser> {
+        self.users_db().get(&user_id(name)).await
+    }
+
+    /// Updates a user that was read with [`get_user`](Self::get_user). The current password is kept, unless a new
+    /// password was set with [`CouchUser::set_password`].
+    pub async fn update_user(&self, user: &mut CouchUser) -> DocumentCreatedResult {
+        self.create_user(user).await
+    }
+
+    /// Changes the password of a user.
+    pub async fn change_password(&self, name: &str, password: &str) -> DocumentCreatedResult {
+        let mut user = self.get_user(name).await?;
+        user.set_password(password);
+        self.update_user(&mut user).await
+    }
+
+    /// Deletes a user.
+    pub async fn delete_user(&self, user: &CouchUser) -> CouchResult<()> {
+        self.users_db().remove(user).await.map(|_| ())
+    }
+
+    /// Lists the running tasks, like compactions, indexers and replications.
+    /// See [_active_tasks](https://docs.couchdb.org/en/stable/api/server/common.html#active-tasks) for more details.
+    pub async fn active_tasks(&self) -> CouchResult<Vec<ActiveTask>> {
+        let response = self.send(self.get("/_active_tasks", None)).await?.error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Waits until none of the running tasks match the filter, checking the active tasks every `poll_interval`.
+    /// Fails with a `REQUEST_TIMEOUT` error when tasks still match after `timeout`.
+    /// Note that a task that has not been started yet can not be waited for.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::{error::CouchResult, types::tasks::ActiveTask};
+    /// use std::time::Duration;
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     client
+    ///         .wait_for_tasks(
+    ///             |task| matches!(task, ActiveTask::Indexer(_)),
+    ///             Duration::from_secs(1),
+    ///             Duration::from_secs(300),
+    ///         )
+    ///         .await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn wait_for_tasks(
+        &self,
+        filter: impl Fn(&ActiveTask) -> bool + Send,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> CouchResult<()> {
+        let deadline = Instant::now() + timeout;
+        while self.active_tasks().await?.iter().any(&filter) {
+            wait_until_deadline(poll_interval, deadline, "tasks").await?;
+        }
+        Ok(())
+    }
+
+    /// Lists the replication jobs known to the scheduler.
+    /// See [_scheduler/jobs](https://docs.couchdb.org/en/stable/api/server/common.html#scheduler-jobs) for more details.
+    pub async fn scheduler_jobs(&self, limit: Option<u64>, skip: Option<u64>) -> CouchResult<SchedulerJobs> {
+        let response = self
+            .send(self.get("/_scheduler/jobs", Some(&paging_params(limit, skip))))
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Lists the state of the replication documents, including completed and failed replications.
+    /// See [_scheduler/docs](https://docs.couchdb.org/en/stable/api/server/common.html#scheduler-docs) for more details.
+    pub async fn scheduler_docs(&self, limit: Option<u64>, skip: Option<u64>) -> CouchResult<SchedulerDocs> {
+        let response = self
+            .send(self.get("/_scheduler/docs", Some(&paging_params(limit, skip))))
+            .await?
+            .error_for_status()?;
+        Ok(response.json().await?)
+    }
+
+    /// Gets the state of a replication document in the `_replicator` database.
+    pub async fn scheduler_doc(&self, doc_id: &str) -> CouchResult<SchedulerDoc> {
+        let path = format!("/_scheduler/docs/{REPLICATOR_DB}/{}", url_encode!(doc_id));
+        let response = self.send(self.get(&path, None)).await?.error_for_status()?;
+        Ok(response.json().await?)
+    }
+
     pub fn req(&self, method: Method, path: &str, opts: Option<&HashMap<String, String>>) -> RequestBuilder {
-        let mut uri = self.uri.clone();
-        uri.set_path(path);
+        let mut uri = server_uri(&self.uri, path);
 
         if let Some(map) = opts {
             let mut qp = uri.query_pairs_mut();
@@ -317,9 +689,83 @@ impl Client {
             }
         }
 
-        self.client
-            .request(method, uri.as_str())
-            .headers(construct_json_headers(Some(uri.as_str())))
+        let mut headers = self.default_headers.clone();
+        headers.extend(construct_json_headers(Some(uri.as_str())));
+        if let Some(auth) = &self.auth {
+            auth.apply(&mut headers);
+        }
+
+        let request = self.client.request(method, uri.as_str()).headers(headers);
+        match self.timeout {
+            Some(timeout) => request.timeout(timeout),
+            None => request,
+        }
+    }
+
+    /// Sends a request that was built with [`req`](Self::req), taking care of authentication and retries.
+    /// Credentials are refreshed when required, e.g. a cookie session is started or renewed, and a request
+    /// that is rejected with `401 Unauthorized` is sent once more when the [`Authenticator`] renewed them.
+    /// Transient failures are retried according to the [`RetryPolicy`], when one is configured.
+    pub async fn send(&self, request: RequestBuilder) -> CouchResult<Response> {
+        let mut request = request.build()?;
+        let Some(policy) = &self.retry else {
+            return self.execute(request).await;
+        };
+
+        let mut attempt = 1;
+        loop {
+            // requests with a streaming body can not be cloned, and are therefore not retried
+            let Some(next) = request.try_clone() else {
+                return self.execute(request).await;
+            };
+
+            let outcome = self.execute(request).await;
+            let Some((delay, reason)) = policy.retry_after(next.method(), next.url(), attempt, &outcome) else {
+                return outcome;
+            };
+
+            policy.notify(&RetryEvent {
+                method: next.method(),
+                url: next.url(),
+                attempt,
+                delay,
+                reason,
+            });
+            tokio::time::sleep(delay).await;
+            request = next;
+            attempt += 1;
+        }
+    }
+
+    /// Sends a request once, taking care of authentication.
+    async fn execute(&self, mut request: Request) -> CouchResult<Response> {
+        let Some(auth) = &self.auth else {
+            return Ok(self.client.execute(request).await?);
+        };
+        let context = AuthContext {
+            client: &self.client,
+            uri: &self.uri,
+        };
+
+        auth.prepare(context).await?;
+        auth.apply(request.headers_mut());
+        // requests with a streaming body can not be cloned, and are therefore not retried
+        let retry = request.try_clone();
+        let response = self.client.execute(request).await?;
+        auth.update(&response);
+
+        match retry {
+            Some(mut retry) if response.status() == StatusCode::UNAUTHORIZED => {
+                if !auth.unauthorized(context, &retry).await? {
+                    return Ok(response);
+                }
+                auth.apply(retry.headers_mut());
+                let response = self.client.execute(retry).await?;
+                auth.update(&response);
+                Ok(response)
+            }
+            _ => Ok(response),
+        }
     }
 
     pub(crate) fn get(&self, path: &str, args: Option<&HashMap<String, String>>) -> RequestBuilder {
@@ -342,3 +788,273 @@ impl Client {
         self.req(Method::DELETE, path, args)
     }
 }
+
+/// A builder to configure the authentication and the HTTP transport of a [`Client`].
+///
+/// Usage:
+/// ```
+/// use couch_rs::{auth::CookieAuth, error::CouchResult, Client};
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() -> CouchResult<()> {
+///     let client = Client::builder("http://localhost:5984")
+///         .auth(CookieAuth::new("admin", "password"))
+///         .timeout(Duration::from_millis(2500))
+///         .user_agent("my-app/1.0")
+///         .pool_max_idle_per_host(8)
+///         .build()?;
+///     let dbs = client.list_dbs().await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug)]
+#[must_use]
+pub struct ClientBuilder {
+    uri: String,
+    auth: Option<Arc<dyn Authenticator>>,
+    retry: Option<RetryPolicy>,
+    db_prefix: String,
+    timeout: Option<Duration>,
+    default_headers: HeaderMap,
+    user_agent: Option<String>,
+    http_client: Option<reqwest::Client>,
+    transport: reqwest::ClientBuilder,
+}
+
+impl ClientBuilder {
+    /// Creates a builder for a client without authentication and with a timeout of 10 seconds.
+    /// The URI has to be in this format: <http://hostname:5984>
+    pub fn new(uri: &str) -> Self {
+        ClientBuilder {
+            uri: uri.to_string(),
+            auth: None,
+            retry: None,
+            db_prefix: String::new(),
+            timeout: Some(Duration::from_secs(DEFAULT_TIME_OUT)),
+            default_headers: HeaderMap::new(),
+            user_agent: None,
+            http_client: None,
+            transport: reqwest::Client::builder().gzip(true),
+        }
+    }
+
+    /// Sets the authentication strategy; one of the strategies in [`auth`](crate::auth), or a custom [`Authenticator`].
+    pub fn auth(mut self, auth: impl Authenticator + 'static) -> Self {
+        self.auth = Some(Arc::new(auth));
+        self
+    }
+
+    /// Sets the policy for retrying requests that failed due to a transient error. Requests are not retried by default.
+    pub fn retry(mut self, policy: RetryPolicy) -> Self {
+        self.retry = Some(policy);
+        self
+    }
+
+    /// Sets the prefix that is prepended to all database names.
+    pub fn db_prefix(mut self, prefix: &str) -> Self {
+        self.db_prefix = prefix.to_string();
+        self
+    }
+
+    /// Sets the timeout of a request. The timeout is applied from when the request starts connecting until the
+    /// response body has finished. Defaults to 10 seconds.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Disables the request timeout.
+    pub fn no_timeout(mut self) -> Self {
+        self.timeout = None;
+        self
+    }
+
+    /// Sets the `User-Agent` header sent with every request. Defaults to `couch_rs/<version>`.
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Sets headers that are sent with every request. Headers set by the client itself, like `Content-Type` or
+    /// the credentials, take precedence.
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.default_headers = headers;
+        self
+    }
+
+    /// Uses an existing `reqwest::Client` to send the requests, for example to share its connection pool.
+    /// The transport settings of this builder are ignored when an HTTP client is provided; the timeout,
+    /// user agent, default headers and authentication are still applied to every request.
+    pub fn http_client(mut self, client: reqwest::Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Enables or disables the automatic decompression of gzip encoded responses. Enabled by default.
+    pub fn gzip(mut self, enable: bool) -> Self {
+        self.transport = self.transport.gzip(enable);
+        self
+    }
+
+    /// Sets the timeout for connecting to the server.
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.transport = self.transport.connect_timeout(timeout);
+        self
+    }
+
+    /// Adds a proxy; see [`reqwest::Proxy`] for the available options.
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.transport = self.transport.proxy(proxy);
+        self
+    }
+
+    /// Disables all proxies, including the system proxies.
+    pub fn no_proxy(mut self) -> Self {
+        self.transport = self.transport.no_proxy();
+        self
+    }
+
+    /// Sets how long idle connections are kept in the pool; `None` keeps them until they are closed by the server.
+    pub fn pool_idle_timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.transport = self.transport.pool_idle_timeout(timeout);
+        self
+    }
+
+    /// Sets the maximum number of idle connections per host.
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.transport = self.transport.pool_max_idle_per_host(max);
+        self
+    }
+
+    /// Only uses HTTP/2; requires the `http2` feature.
+    #[cfg(feature = "http2")]
+    pub fn http2_prior_knowledge(mut self) -> Self {
+        self.transport = self.transport.http2_prior_knowledge();
+        self
+    }
+
+    /// Adds a trusted root certificate, for example for a server with a self-signed certificate.
+    /// Requires one of the TLS features.
+    #[cfg(feature = "__tls")]
+    pub fn add_root_certificate(mut self, certificate: reqwest::Certificate) -> Self {
+        self.transport = self.transport.add_root_certificate(certificate);
+        self
+    }
+
+    /// Sets the client certificate, for servers that require mutual TLS. Requires one of the TLS features.
+    #[cfg(feature = "__tls")]
+    pub fn identity(mut self, identity: reqwest::Identity) -> Self {
+        self.transport = self.transport.identity(identity);
+        self
+    }
+
+    /// Creates the client.
+    pub fn build(self) -> CouchResult<Client> {
+        let user_agent = self.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT);
+        let mut default_headers = self.default_headers;
+        let user_agent = HeaderValue::from_str(user_agent)
+            .map_err(|_| CouchError::new(format!("invalid user agent: {user_agent}"), StatusCode::BAD_REQUEST))?;
+        default_headers.insert(USER_AGENT, user_agent);
+
+        let client = match self.http_client {
+            Some(client) => client,
+            None => self.transport.build()?,
+        };
+
+        Ok(Client {
+            client,
+            timeout: self.timeout,
+            default_headers,
+            uri: parse_server(&self.uri)?,
+            auth: self.auth,
+            retry: self.retry,
+            db_prefix: self.db_prefix,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_build_requests_with_transport_settings() {
+        let mut default_headers = HeaderMap::new();
+        default_headers.insert("x-app", HeaderValue::from_static("test"));
+        default_headers.insert(CONTENT_TYPE, HeaderValue::from_static("text/plain"));
+
+        let client = Client::builder("http://localhost:5984")
+            .timeout(Duration::from_millis(1500))
+            .user_agent("my-app/1.0")
+            .default_headers(default_headers)
+            .http_client(reqwest::Client::new())
+            .build()
+            .unwrap();
+
+        let request = client.get("/_all_dbs", None).build().unwrap();
+        assert_eq!(request.timeout(), Some(&Duration::from_millis(1500)));
+        assert_eq!(request.headers()[USER_AGENT], "my-app/1.0");
+        assert_eq!(request.headers()["x-app"], "test");
+        assert_eq!(request.headers()[CONTENT_TYPE], "application/json");
+    }
+
+    #[tokio::test]
+    async fn should_retry_connection_errors() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let retries = Arc::new(AtomicU32::new(0));
+        let policy = RetryPolicy::new(3)
+            .backoff(Duration::from_millis(1), Duration::from_millis(5))
+            .on_retry({
+                let retries = retries.clone();
+                move |event| {
+                    assert!(event.attempt < 3);
+                    retries.fetch_add(1, Ordering::SeqCst);
+                }
+            });
+
+        // nothing listens on port 1, so the connection is refused before anything is sent
+        let client = Client::builder("http://localhost:1").retry(policy).build().unwrap();
+        let mut doc = serde_json::json!({ "thing": true });
+        let db = Database::new("db".to_string(), client.clone());
+        assert!(client.list_dbs().await.is_err());
+        assert!(db.save(&mut doc).await.is_err());
+        assert_eq!(retries.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn keeps_the_path_of_the_server_uri() {
+        let client = Client::new_no_auth("http://localhost:8080/couchdb/").unwrap();
+        let request = client.get("/_all_dbs", None).build().unwrap();
+        assert_eq!(request.url().as_str(), "http://localhost:8080/couchdb/_all_dbs");
+
+        let root = Url::parse("http://localhost:5984").unwrap();
+        assert_eq!(server_uri(&root, "db/doc").as_str(), "http://localhost:5984/db/doc");
+        let prefixed = Url::parse("http://localhost:8080/couchdb").unwrap();
+        assert_eq!(
+            server_uri(&prefixed, "_session").as_str(),
+            "http://localhost:8080/couchdb/_session"
+        );
+    }
+
+    #[tokio::test]
+    async fn should_time_out_waiting() {
+        let deadline = Instant::now() + Duration::from_millis(20);
+        wait_until_deadline(Duration::from_secs(60), deadline, "tasks")
+            .await
+            .expect("the deadline has not passed yet");
+        let err = wait_until_deadline(Duration::from_millis(1), deadline, "tasks")
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), Some(StatusCode::REQUEST_TIMEOUT));
+    }
+
+    #[test]
+    fn has_a_default_user_agent() {
+        let client = Client::new_no_auth("http://localhost:5984").unwrap();
+        let request = client.get("/", None).build().unwrap();
+        assert_eq!(request.headers()[USER_AGENT], DEFAULT_USER_AGENT);
+        assert_eq!(request.timeout(), Some(&Duration::from_secs(DEFAULT_TIME_OUT)));
+    }
+}
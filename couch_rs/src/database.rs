@@ -1,25 +1,64 @@
 use crate::{
     changes::ChangesStream,
-    client::{is_accepted, is_ok, Client},
-    document::{DocumentCollection, TypedCouchDocument, ID_FIELD, REV_FIELD},
+    client::{is_accepted, is_ok, wait_until_deadline, Client},
+    document::{
+        get_mandatory_string_value, to_document, DocumentCollection, TypedCouchDocument, ATTACHMENTS_FIELD,
+        CONFLICTS_FIELD, DELETED_CONFLICTS_FIELD, DELETED_FIELD, DESIGN_PREFIX, ID_FIELD, LOCAL_PREFIX,
+        LOCAL_SEQ_FIELD, REVISIONS_FIELD, REVS_INFO_FIELD, REV_FIELD,
+    },
     error::{CouchError, CouchResult, ErrorMessage},
+    multipart,
+    partition::Partition,
     types::{
-        design::DesignCreated,
+        attachment::{digest_from_etag, Attachment, AttachmentEncoding, AttachmentStream, DocumentWithAttachments},
+        bulk_get::{BulkGetDoc, BulkGetRequest, BulkGetResponse, BulkGetResult},
+        conflicts::{ConflictResolver, DocumentWithConflicts},
+        design::{DesignCreated, DesignDocument},
         design_info::DesignInfo,
-        document::{DocumentCreatedDetails, DocumentCreatedResponse, DocumentCreatedResult, DocumentId},
+        document::{
+            DocumentCreatedDetails, DocumentCreatedResponse, DocumentCreatedResult, DocumentId, DocumentWritten,
+        },
         find::{FindQuery, FindResult},
         index::{DatabaseIndexList, DeleteIndexResponse, IndexFields, IndexType},
+        options::{DocumentWithMeta, GetOptions, WriteOptions},
+        patch::Patch,
+        purge::PurgeResult,
         query::{QueriesCollection, QueriesParams, QueryParams},
+        revisions::{
+            chunk_revs, DocumentRevisions, MissingRevs, OpenRevision, OpenRevs, RevisionInfo, Revisions,
+            RevsByDocument, RevsDiff,
+        },
+        security::SecurityObject,
+        system::DbInfo,
+        tasks::ActiveTask,
+        update::{UpdateAction, UpdateResult},
         view::ViewCollection,
     },
 };
-use futures_core::Future;
-use reqwest::StatusCode;
+use base64::{engine::general_purpose, Engine};
+use bytes::Bytes;
+use futures_core::{Future, TryStream};
+use futures_util::TryStreamExt;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::{
+    header::{HeaderMap, HeaderValue, ACCEPT, CONTENT_TYPE, ETAG},
+    Body, Method, RequestBuilder, Response, StatusCode,
+};
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::{from_value, json, to_string, Value};
-use std::{collections::HashMap, fmt::Debug, pin::Pin, sync::Arc};
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    pin::Pin,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::sync::mpsc::Sender;
 
+/// Characters that are percent-encoded in a path segment; unlike form encoding, a space becomes `%20`,
+/// as `CouchDB` does not decode `+` in a path.
+const PATH_SEGMENT: &AsciiSet = &NON_ALPHANUMERIC.remove(b'-').remove(b'.').remove(b'_').remove(b'~');
+
 trait CouchJsonExt {
     fn couch_json<T: DeserializeOwned>(self) -> Pin<Box<dyn Future<Output = Result<T, CouchError>> + Send>>;
 }
@@ -63,15 +102,61 @@ impl Database {
         &self.name
     }
 
+    pub(crate) fn client(&self) -> &Client {
+        &self.client
+    }
+
+    /// A handle for the queries on a partition of a partitioned database; fails when the partition name is
+    /// invalid.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::error::CouchResult;
+    /// use couch_rs::types::find::FindQuery;
+    /// use couch_rs::types::options::CreateDbOptions;
+    /// use serde_json::{json, Value};
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client
+    ///         .make_db_with_options("sensors", &CreateDbOptions::default().partitioned(true))
+    ///         .await?;
+    ///     let partition = db.partition("sensor-1")?;
+    ///     let mut reading = json!({"_id": partition.doc_id("reading-1")?, "temperature": 21});
+    ///     db.create(&mut reading).await?;
+    ///
+    ///     let warm = partition
+    ///         .find::<Value>(&FindQuery::new(json!({"temperature": {"$gt": 20}})))
+    ///         .await?;
+    ///     println!("{} warm readings", warm.rows.len());
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn partition(&self, name: &str) -> CouchResult<Partition> {
+        Partition::new(self.clone(), name)
+    }
+
     fn create_raw_path(&self, id: &str) -> String {
         format!("{}/{}", self.name, id)
     }
 
     fn create_document_path(&self, id: &str) -> String {
+        // the slash of local document ids is part of the path
+        if let Some(local_id) = id.strip_prefix(LOCAL_PREFIX) {
+            let encoded = url_encode!(local_id);
+            return format!("{}/{}{}", self.name, LOCAL_PREFIX, encoded);
+        }
         let encoded = url_encode!(id);
         format!("{}/{}", self.name, encoded)
     }
 
+    fn create_attachment_path(&self, id: &str, name: &str) -> String {
+        let encoded_id = utf8_percent_encode(id, PATH_SEGMENT);
+        let encoded_name = utf8_percent_encode(name, PATH_SEGMENT);
+        format!("{}/{}/{}", self.name, encoded_id, encoded_name)
+    }
+
     fn create_design_path(&self, id: &str) -> String {
         let encoded = url_encode!(id);
         format!("{}/_design/{}", self.name, encoded)
@@ -104,7 +189,7 @@ impl Database {
         path.push_str("/_compact");
 
         let request = self.client.post(&path, String::new());
-        is_accepted(request).await
+        is_accepted(&self.client, request).await
     }
 
     /// Starts the compaction of all views
@@ -113,13 +198,58 @@ impl Database {
         path.push_str("/_view_cleanup");
 
         let request = self.client.post(&path, String::new());
-        is_accepted(request).await
+        is_accepted(&self.client, request).await
     }
 
     /// Starts the compaction of a given index
     pub async fn compact_index(&self, index: &str) -> bool {
         let request = self.client.post(&self.create_compact_path(index), String::new());
-        is_accepted(request).await
+        is_accepted(&self.client, request).await
+    }
+
+    /// Waits until the compaction of this database and its views has finished, checking the database information
+    /// and the active tasks every `poll_interval`. Use after [`compact`](Self::compact) or
+    /// [`compact_index`](Self::compact_index). Fails with a `REQUEST_TIMEOUT` error when the compaction is still
+    /// running after `timeout`.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::error::CouchResult;
+    /// use std::time::Duration;
+    ///
+    /// const TEST_DB: &str = "test_db";
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client.db(TEST_DB).await?;
+    ///     if db.compact().await {
+    ///         db.wait_for_compaction(Duration::from_millis(500), Duration::from_secs(600)).await?;
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn wait_for_compaction(&self, poll_interval: Duration, timeout: Duration) -> CouchResult<()> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            // the database information reports a compaction as soon as it has started, before it shows up as a task
+            let info: DbInfo = self
+                .client
+                .send(self.client.get(&self.name, None))
+                .await?
+                .error_for_status()?
+                .couch_json()
+                .await?;
+            let compacting = info.compact_running
+                || self.client.active_tasks().await?.iter().any(|task| {
+                    matches!(task, ActiveTask::DatabaseCompaction(_) | ActiveTask::ViewCompaction(_))
+                        && task.is_for_database(&self.name)
+                });
+            if !compacting {
+                return Ok(());
+            }
+            wait_until_deadline(poll_interval, deadline, "compaction").await?;
+        }
     }
 
     /// Checks if a document ID exists
@@ -145,7 +275,7 @@ impl Database {
     /// ```
     pub async fn exists(&self, id: &str) -> bool {
         let request = self.client.head(&self.create_document_path(id), None);
-        is_ok(request).await
+        is_ok(&self.client, request).await
     }
 
     /// Convenience wrapper around `get::`<Value>(id)
@@ -206,18 +336,126 @@ impl Database {
     pub async fn get<T: TypedCouchDocument>(&self, id: &str) -> CouchResult<T> {
         let value: Value = self
             .client
-            .get(&self.create_document_path(id), None)
-            .send()
+            .send(self.client.get(&self.create_document_path(id), None))
+            .await?
+            .error_for_status()?
+            .couch_json()
+            .await?;
+        to_document(value)
+    }
+
+    /// Gets one document with the given read options, together with the metadata they request.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::error::CouchResult;
+    /// use couch_rs::types::options::GetOptions;
+    /// use serde_json::Value;
+    ///
+    /// const TEST_DB: &str = "test_db";
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client.db(TEST_DB).await?;
+    ///
+    ///     let options = GetOptions::default().local_seq(true).meta(true);
+    ///     let result = db.get_with_options::<Value>("1234", &options).await?;
+    ///     println!("{:?} has conflicts: {:?}", result.local_seq, result.conflicts);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_with_options<T: TypedCouchDocument>(
+        &self,
+        id: &str,
+        options: &GetOptions,
+    ) -> CouchResult<DocumentWithMeta<T>> {
+        let params = options.to_params()?;
+        let mut value: Value = self
+            .client
+            .send(self.client.get(&self.create_document_path(id), Some(&params)))
             .await?
             .error_for_status()?
             .couch_json()
             .await?;
-        let id = get_mandatory_string_value(ID_FIELD, &value)?;
-        let rev = get_mandatory_string_value(REV_FIELD, &value)?;
-        let mut document: T = from_value(value)?;
-        document.set_id(&id);
-        document.set_rev(&rev);
-        Ok(document)
+
+        let local_seq = take_field(&mut value, LOCAL_SEQ_FIELD)?;
+        let revs_info = take_field(&mut value, REVS_INFO_FIELD)?;
+        let conflicts = take_field(&mut value, CONFLICTS_FIELD)?;
+        let deleted_conflicts = take_field(&mut value, DELETED_CONFLICTS_FIELD)?;
+        Ok(DocumentWithMeta {
+            doc: to_document(value)?,
+            local_seq,
+            revs_info: revs_info.unwrap_or_default(),
+            conflicts: conflicts.unwrap_or_default(),
+            deleted_conflicts: deleted_conflicts.unwrap_or_default(),
+        })
+    }
+
+    /// Gets one document, together with the content of all its attachments. The attachments are requested
+    /// as a `multipart/related` response, so they are transferred without base64 overhead.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::error::CouchResult;
+    /// use couch_rs::types::attachment::{Attachment, AttachmentEncoding};
+    /// use serde_json::{json, Value};
+    /// use std::collections::HashMap;
+    ///
+    /// const TEST_DB: &str = "test_db";
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client.db(TEST_DB).await?;
+    ///
+    ///     let mut attachments = HashMap::new();
+    ///     attachments.insert("hello.txt".to_string(), Attachment::new("text/plain", "hello world"));
+    ///     let mut doc = json!({"_id": "greeting"});
+    ///     db.save_with_attachments(&mut doc, &attachments, AttachmentEncoding::Multipart).await?;
+    ///
+    ///     let result = db.get_with_attachments::<Value>("greeting").await?;
+    ///     assert_eq!(result.attachments["hello.txt"].data, "hello world");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_with_attachments<T: TypedCouchDocument>(
+        &self,
+        id: &str,
+    ) -> CouchResult<DocumentWithAttachments<T>> {
+        let mut h = HashMap::new();
+        h.insert(s!("attachments"), s!("true"));
+
+        let response = self
+            .client
+            .send(
+                self.client
+                    .get(&self.create_document_path(id), Some(&h))
+                    .header(ACCEPT, "multipart/related"),
+            )
+            .await?
+            .error_for_status()?;
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+
+        let (value, attachments) = if let Some(boundary) = multipart_boundary(&content_type) {
+            let body = response.bytes().await?;
+            parse_document_with_attachments(&multipart::parse(&body, &boundary)?)?
+        } else {
+            let value: Value = response.couch_json().await?;
+            let attachments = inline_attachments(&value)?;
+            (value, attachments)
+        };
+
+        Ok(DocumentWithAttachments {
+            doc: to_document(value)?,
+            attachments,
+        })
     }
 
     /// Gets documents in bulk with provided IDs list
@@ -230,6 +468,123 @@ impl Database {
         self.get_bulk_params(ids, None).await
     }
 
+    /// Gets documents in bulk through `_bulk_get`, optionally at a specific revision. Returns one result per
+    /// requested document, in request order, which tells a missing document apart from a deleted one.
+    /// With `revs`, each document includes its revision history in `_revisions`, as replication does.
+    /// See [_bulk_get](https://docs.couchdb.org/en/stable/api/database/bulk-api.html#db-bulk-get) for more details.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::error::CouchResult;
+    /// use couch_rs::types::bulk_get::BulkGetResult;
+    /// use serde_json::Value;
+    ///
+    /// const TEST_DB: &str = "test_db";
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client.db(TEST_DB).await?;
+    ///
+    ///     let docs = vec![("1234".to_string(), None), ("5678".to_string(), Some("2-abc".to_string()))];
+    ///     for result in db.bulk_get::<Value>(docs, false).await? {
+    ///         match result {
+    ///             BulkGetResult::Found(doc) => println!("found {}", doc["_id"]),
+    ///             BulkGetResult::NotFound { id, .. } => println!("{id} does not exist"),
+    ///             BulkGetResult::Deleted { id, .. } => println!("{id} was deleted"),
+    ///             BulkGetResult::Error(err) => println!("{err}"),
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn bulk_get<T: TypedCouchDocument>(
+        &self,
+        docs: Vec<(DocumentId, Option<String>)>,
+        revs: bool,
+    ) -> CouchResult<Vec<BulkGetResult<T>>> {
+        let body = BulkGetRequest {
+            docs: docs.into_iter().map(|(id, rev)| BulkGetDoc { id, rev }).collect(),
+        };
+        let mut request = self.client.post(&self.create_raw_path("_bulk_get"), to_string(&body)?);
+        if revs {
+            request = request.query(&[("revs", "true")]);
+        }
+        let response: BulkGetResponse = self
+            .client
+            .send(request)
+            .await?
+            .error_for_status()?
+            .couch_json()
+            .await?;
+
+        response.results.into_iter().map(|item| item.into_result()).collect()
+    }
+
+    /// Given a set of document revisions, returns the ones that do not exist in the database, with the
+    /// revisions that do exist and may be their ancestors. Documents without missing revisions are left out.
+    /// Large inputs are sent in several requests.
+    /// See [_revs_diff](https://docs.couchdb.org/en/stable/api/database/misc.html#db-revs-diff) for more details.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::error::CouchResult;
+    /// use couch_rs::types::revisions::RevsByDocument;
+    ///
+    /// const TEST_DB: &str = "test_db";
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client.db(TEST_DB).await?;
+    ///
+    ///     let mut revs = RevsByDocument::new();
+    ///     revs.insert("1234".to_string(), vec!["2-abc".to_string(), "3-def".to_string()]);
+    ///     for (id, diff) in db.revs_diff(&revs).await? {
+    ///         println!("{id} misses {:?}", diff.missing);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn revs_diff(&self, revs: &RevsByDocument) -> CouchResult<HashMap<DocumentId, RevsDiff>> {
+        let mut result = HashMap::new();
+        for chunk in chunk_revs(revs, REVS_CHUNK_SIZE) {
+            let diff: HashMap<DocumentId, RevsDiff> = self
+                .client
+                .send(
+                    self.client
+                        .post(&self.create_raw_path("_revs_diff"), to_string(&chunk)?),
+                )
+                .await?
+                .error_for_status()?
+                .couch_json()
+                .await?;
+            result.extend(diff);
+        }
+        Ok(result)
+    }
+
+    /// Given a set of document revisions, returns the ones that do not exist in the database.
+    /// Documents without missing revisions are left out. Large inputs are sent in several requests.
+    /// See [_missing_revs](https://docs.couchdb.org/en/stable/api/database/misc.html#db-missing-revs) for more details.
+    pub async fn missing_revs(&self, revs: &RevsByDocument) -> CouchResult<RevsByDocument> {
+        let mut result = RevsByDocument::new();
+        for chunk in chunk_revs(revs, REVS_CHUNK_SIZE) {
+            let missing: MissingRevs = self
+                .client
+                .send(
+                    self.client
+                        .post(&self.create_raw_path("_missing_revs"), to_string(&chunk)?),
+                )
+                .await?
+                .error_for_status()?
+                .couch_json()
+                .await?;
+            result.extend(missing.missing_revs);
+        }
+        Ok(result)
+    }
+
     /// Each time a document is stored or updated in `CouchDB`, the internal B-tree is updated.
     /// Bulk insertion provides efficiency gains in both storage space, and time,
     /// by consolidating many of the updates to intermediate B-tree nodes.
@@ -265,20 +620,72 @@ impl Database {
     pub async fn bulk_docs<T: TypedCouchDocument>(
         &self,
         raw_docs: &mut [T],
+    ) -> CouchResult<Vec<DocumentCreatedResult>> {
+        self.bulk_docs_with_options(raw_docs, &WriteOptions::default()).await
+    }
+
+    /// Like [`bulk_docs`](Self::bulk_docs), with write options. With `new_edits` disabled, the documents are
+    /// stored with the revisions they have, which is how data is copied between databases without replication;
+    /// the revision ids are kept verbatim. Batch mode does not apply to `_bulk_docs`.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::error::CouchResult;
+    /// use couch_rs::types::options::WriteOptions;
+    /// use serde_json::json;
+    ///
+    /// const TEST_DB: &str = "test_db";
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client.db(TEST_DB).await?;
+    ///
+    ///     let mut docs = vec![json!({"_id": "imported", "_rev": "3-917fa2381192822767f010b95b45325b"})];
+    ///     let results = db.bulk_docs_with_options(&mut docs, &WriteOptions::default().new_edits(false)).await?;
+    ///     assert_eq!(results[0].as_ref().unwrap().rev, "3-917fa2381192822767f010b95b45325b");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn bulk_docs_with_options<T: TypedCouchDocument>(
+        &self,
+        raw_docs: &mut [T],
+        options: &WriteOptions,
     ) -> CouchResult<Vec<DocumentCreatedResult>> {
         let upsert_values: Vec<_> = raw_docs
             .iter()
             .map(|doc| to_upsert_value(doc))
             .collect::<CouchResult<_>>()?;
-        let body = format!(r#"{{"docs":{} }}"#, to_string(&upsert_values)?);
+        let mut body = json!({ "docs": upsert_values });
+        if let Some(new_edits) = options.new_edits {
+            body["new_edits"] = json!(new_edits);
+        }
         let response = self
             .client
-            .post(&self.create_raw_path("_bulk_docs"), body)
-            .send()
+            .send(self.client.post(&self.create_raw_path("_bulk_docs"), body.to_string()))
             .await?;
 
         let data: Vec<DocumentCreatedResponse> = response.json().await?;
 
+        if options.new_edits == Some(false) {
+            // only documents that could not be stored are listed, by id; as the response does not tell documents
+            // with the same id apart, each of them is reported with the error
+            let errors: HashMap<String, DocumentCreatedResponse> = data
+                .into_iter()
+                .filter_map(|response| response.id.clone().map(|id| (id, response)))
+                .collect();
+            return Ok(raw_docs
+                .iter()
+                .map(|doc| match errors.get(doc.get_id().as_ref()) {
+                    Some(response) if response.error.is_some() => response.clone().into(),
+                    _ => Ok(DocumentCreatedDetails {
+                        id: doc.get_id().into_owned(),
+                        rev: doc.get_rev().into_owned(),
+                    }),
+                })
+                .collect());
+        }
+
         if raw_docs.len() != data.len() {
             return Err(CouchError::new(
                 format!(
@@ -291,7 +698,7 @@ impl Database {
         }
         let result = raw_docs
             .iter_mut()
-            .zip(data.into_iter())
+            .zip(data)
             .map(|(doc, response): (&mut T, DocumentCreatedResponse)| {
                 let result: DocumentCreatedResult = response.into();
                 match result {
@@ -307,15 +714,48 @@ impl Database {
         Ok(result)
     }
 
-    /// Gets documents in bulk with provided IDs list, with added params. Params description can be found here:
-    /// [_all_docs](https://docs.couchdb.org/en/latest/api/database/bulk-api.html?highlight=_all_docs)
+    /// Gets a document together with its conflicting revisions. `CouchDB` picks one of the revisions as the winner;
+    /// the other, conflicting, revisions are left behind by concurrent edits, typically on replicated databases.
+    /// See [replication and conflicts](https://docs.couchdb.org/en/stable/replication/conflicts.html) for more details.
+    pub async fn get_with_conflicts<T: TypedCouchDocument>(&self, id: &str) -> CouchResult<DocumentWithConflicts<T>> {
+        let mut params = HashMap::new();
+        params.insert(s!("conflicts"), s!("true"));
+        let mut value: Value = self
+            .client
+            .send(self.client.get(&self.create_document_path(id), Some(&params)))
+            .await?
+            .error_for_status()?
+            .couch_json()
+            .await?;
+
+        let conflicts: Vec<String> = match value.as_object_mut().and_then(|doc| doc.remove(CONFLICTS_FIELD)) {
+            Some(conflicts) => from_value(conflicts)?,
+            None => vec![],
+        };
+        let doc = to_document(value)?;
+        let conflicts = if conflicts.is_empty() {
+            vec![]
+        } else {
+            self.get_open_revs(id, OpenRevs::Specific(conflicts))
+                .await?
+                .into_iter()
+                .filter_map(|revision| match revision {
+                    OpenRevision::Found(doc) => Some(doc),
+                    OpenRevision::Deleted(_) | OpenRevision::Missing(_) => None,
+                })
+                .collect()
+        };
+
+        Ok(DocumentWithConflicts { doc, conflicts })
+    }
+
+    /// Resolves the conflicts of a document: the resolver merges the winning and the conflicting revisions into a
+    /// new winner, which is written together with the deletion of the conflicting revisions in a single `_bulk_docs`
+    /// request. Returns the new winner, or the current document when it had no conflicts.
     ///
     /// Usage:
-    ///
     /// ```
-    /// use couch_rs::types::find::FindQuery;
-    /// use couch_rs::error::CouchResult;
-    /// use serde_json::json;
+    /// use couch_rs::{error::CouchResult, types::conflicts::LastWriteWins};
     /// use serde_json::Value;
     ///
     /// const TEST_DB: &str = "test_db";
@@ -324,73 +764,250 @@ impl Database {
     /// async fn main() -> CouchResult<()> {
     ///     let client = couch_rs::Client::new_local_test()?;
     ///     let db = client.db(TEST_DB).await?;
-    ///     let mut doc_1 = json!({
-    ///                     "_id": "john",
-    ///                     "first_name": "John",
-    ///                     "last_name": "Doe"
-    ///                 });
-    ///
-    ///     let mut doc_2 = json!({
-    ///                     "_id": "jane",
-    ///                     "first_name": "Jane",
-    ///                     "last_name": "Doe"
-    ///                 });
-    ///
-    ///     // Save these documents
-    ///     db.save(&mut doc_1).await?;
-    ///     db.save(&mut doc_2).await?;
-    ///
-    ///     // subsequent call updates the existing document
-    ///     let docs = db.get_bulk_params::<Value>(vec!["john".to_string(), "jane".to_string()], None).await?;
     ///
-    ///     // verify that we received the 2 documents
-    ///     assert_eq!(docs.rows.len(), 2);
+    ///     let resolved: Value = db.resolve_conflicts("1234", &LastWriteWins::new("updated_at")).await?;
+    ///     // or merge the revisions yourself
+    ///     let merged: Value = db
+    ///         .resolve_conflicts("1234", &|winner: &Value, _conflicts: &[Value]| winner.clone())
+    ///         .await?;
     ///     Ok(())
     /// }
     /// ```
-    pub async fn get_bulk_params<T: TypedCouchDocument>(
+    pub async fn resolve_conflicts<T: TypedCouchDocument>(
         &self,
-        ids: Vec<DocumentId>,
-        params: Option<QueryParams<DocumentId>>,
-    ) -> CouchResult<DocumentCollection<T>> {
-        let mut options = params.unwrap_or_default();
+        id: &str,
+        resolver: &impl ConflictResolver<T>,
+    ) -> CouchResult<T> {
+        let DocumentWithConflicts { doc, conflicts } = self.get_with_conflicts(id).await?;
+        if conflicts.is_empty() {
+            return Ok(doc);
+        }
 
-        options.include_docs = Some(true);
-        options.keys = ids;
+        let mut merged = resolver.resolve(&doc, &conflicts);
+        merged.set_id(&doc.get_id());
+        merged.set_rev(&doc.get_rev());
 
+        let mut docs = vec![Value::Object(to_upsert_value(&merged)?)];
+        docs.extend(conflicts.iter().map(|conflict| {
+            json!({
+                ID_FIELD: conflict.get_id(),
+                REV_FIELD: conflict.get_rev(),
+                DELETED_FIELD: true,
+            })
+        }));
         let response = self
             .client
-            .post(&self.create_raw_path("_all_docs"), to_string(&options)?)
-            .send()
+            .send(self.client.post(
+                &self.create_raw_path("_bulk_docs"),
+                to_string(&json!({ "docs": docs }))?,
+            ))
             .await?
             .error_for_status()?;
 
-        Ok(DocumentCollection::new(response.couch_json().await?))
-    }
+        let results: Vec<DocumentCreatedResponse> = response.couch_json().await?;
+        let mut results = results.into_iter().map(DocumentCreatedResult::from);
+        let winner = results.next().ok_or_else(|| {
+            CouchError::new(
+                s!("no result received for the resolved document"),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )
+        })??;
+        // all conflicts must be gone; a failure here typically means a concurrent resolution
+        for result in results {
+            result?;
+        }
 
-    /// Gets all the documents in database
-    pub async fn get_all<T: TypedCouchDocument>(&self) -> CouchResult<DocumentCollection<T>> {
-        self.get_all_params(None).await
+        merged.set_rev(&winner.rev);
+        Ok(merged)
     }
 
-    /// Gets all the documents in database as raw Values
-    pub async fn get_all_raw(&self) -> CouchResult<DocumentCollection<Value>> {
-        self.get_all_params(None).await
+    /// Gets a specific revision of a document. Older revisions are only available until the database is compacted.
+    pub async fn get_rev<T: TypedCouchDocument>(&self, id: &str, rev: &str) -> CouchResult<T> {
+        let mut params = HashMap::new();
+        params.insert(s!("rev"), rev.to_string());
+        let value: Value = self
+            .client
+            .send(self.client.get(&self.create_document_path(id), Some(&params)))
+            .await?
+            .error_for_status()?
+            .couch_json()
+            .await?;
+        to_document(value)
     }
 
-    /// Gets all documents in the database, using bookmarks to iterate through all the documents.
-    /// Results are returned through an mpcs channel for async processing. Use this for very large
-    /// databases only. Batch size can be requested. A value of 0, means the default `batch_size` of
-    /// 1000 is used. `max_results` of 0 means all documents will be returned. A given `max_results` is
-    /// always rounded *up* to the nearest multiplication of `batch_size`.
-    /// This operation is identical to `find_batched(FindQuery::find_all()`, tx, `batch_size`, `max_results`)
+    /// Gets a document together with its revision history, and the availability of each of its revisions.
+    /// See [revisions](https://docs.couchdb.org/en/stable/api/document/common.html#obtaining-an-extended-revision-history)
+    /// for more details.
     ///
-    /// Check out the `async_batch_read` example for usage details
-    pub async fn get_all_batched<T: TypedCouchDocument>(
-        &self,
-        tx: Sender<DocumentCollection<T>>,
-        batch_size: u64,
-        max_results: u64,
+    /// Usage:
+    /// ```
+    /// use couch_rs::error::CouchResult;
+    /// use serde_json::Value;
+    ///
+    /// const TEST_DB: &str = "test_db";
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client.db(TEST_DB).await?;
+    ///
+    ///     let history = db.get_revisions::<Value>("1234").await?;
+    ///     for info in history.revs_info {
+    ///         println!("{}: {:?}", info.rev, info.status);
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_revisions<T: TypedCouchDocument>(&self, id: &str) -> CouchResult<DocumentRevisions<T>> {
+        let mut params = HashMap::new();
+        params.insert(s!("revs"), s!("true"));
+        params.insert(s!("revs_info"), s!("true"));
+        let mut value: Value = self
+            .client
+            .send(self.client.get(&self.create_document_path(id), Some(&params)))
+            .await?
+            .error_for_status()?
+            .couch_json()
+            .await?;
+
+        let revisions: Option<Revisions> = take_field(&mut value, REVISIONS_FIELD)?;
+        let revs_info: Option<Vec<RevisionInfo>> = take_field(&mut value, REVS_INFO_FIELD)?;
+        Ok(DocumentRevisions {
+            doc: to_document(value)?,
+            revisions: revisions.map(|r| r.revs()).unwrap_or_default(),
+            revs_info: revs_info.unwrap_or_default(),
+        })
+    }
+
+    /// Gets leaf revisions of a document: all of them, including conflicts and deleted leaves, or the given ones.
+    /// Requested revisions that do not exist are returned as [`OpenRevision::Missing`].
+    /// See [`open_revs`](https://docs.couchdb.org/en/stable/api/document/common.html#get--db-docid) for more details.
+    pub async fn get_open_revs<T: TypedCouchDocument>(
+        &self,
+        id: &str,
+        open_revs: OpenRevs,
+    ) -> CouchResult<Vec<OpenRevision<T>>> {
+        let mut params = HashMap::new();
+        let open_revs = match open_revs {
+            OpenRevs::All => s!("all"),
+            OpenRevs::Specific(revs) => to_string(&revs)?,
+        };
+        params.insert(s!("open_revs"), open_revs);
+        let response = self
+            .client
+            .send(
+                self.client
+                    .get(&self.create_document_path(id), Some(&params))
+                    .header(ACCEPT, "multipart/mixed, application/json;q=0.9"),
+            )
+            .await?
+            .error_for_status()?;
+
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+
+        if let Some(boundary) = multipart_boundary(&content_type) {
+            let body = response.bytes().await?;
+            multipart::parse(&body, &boundary)?
+                .iter()
+                .map(open_revision_from_part)
+                .collect()
+        } else {
+            let results: Vec<Value> = response.couch_json().await?;
+            results.into_iter().map(open_revision_from_json).collect()
+        }
+    }
+
+    /// Gets documents in bulk with provided IDs list, with added params. Params description can be found here:
+    /// [_all_docs](https://docs.couchdb.org/en/latest/api/database/bulk-api.html?highlight=_all_docs)
+    ///
+    /// Usage:
+    ///
+    /// ```
+    /// use couch_rs::types::find::FindQuery;
+    /// use couch_rs::error::CouchResult;
+    /// use serde_json::json;
+    /// use serde_json::Value;
+    ///
+    /// const TEST_DB: &str = "test_db";
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client.db(TEST_DB).await?;
+    ///     let mut doc_1 = json!({
+    ///                     "_id": "john",
+    ///                     "first_name": "John",
+    ///                     "last_name": "Doe"
+    ///                 });
+    ///
+    ///     let mut doc_2 = json!({
+    ///                     "_id": "jane",
+    ///                     "first_name": "Jane",
+    ///                     "last_name": "Doe"
+    ///                 });
+    ///
+    ///     // Save these documents
+    ///     db.save(&mut doc_1).await?;
+    ///     db.save(&mut doc_2).await?;
+    ///
+    ///     // subsequent call updates the existing document
+    ///     let docs = db.get_bulk_params::<Value>(vec!["john".to_string(), "jane".to_string()], None).await?;
+    ///
+    ///     // verify that we received the 2 documents
+    ///     assert_eq!(docs.rows.len(), 2);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_bulk_params<T: TypedCouchDocument>(
+        &self,
+        ids: Vec<DocumentId>,
+        params: Option<QueryParams<DocumentId>>,
+    ) -> CouchResult<DocumentCollection<T>> {
+        let mut options = params.unwrap_or_default();
+
+        options.include_docs = Some(true);
+        options.keys = ids;
+
+        let response = self
+            .client
+            .send(
+                self.client
+                    .post(&self.create_raw_path("_all_docs"), to_string(&options)?),
+            )
+            .await?
+            .error_for_status()?;
+
+        Ok(DocumentCollection::new(response.couch_json().await?))
+    }
+
+    /// Gets all the documents in database
+    pub async fn get_all<T: TypedCouchDocument>(&self) -> CouchResult<DocumentCollection<T>> {
+        self.get_all_params(None).await
+    }
+
+    /// Gets all the documents in database as raw Values
+    pub async fn get_all_raw(&self) -> CouchResult<DocumentCollection<Value>> {
+        self.get_all_params(None).await
+    }
+
+    /// Gets all documents in the database, using bookmarks to iterate through all the documents.
+    /// Results are returned through an mpcs channel for async processing. Use this for very large
+    /// databases only. Batch size can be requested. A value of 0, means the default `batch_size` of
+    /// 1000 is used. `max_results` of 0 means all documents will be returned. A given `max_results` is
+    /// always rounded *up* to the nearest multiplication of `batch_size`.
+    /// This operation is identical to `find_batched(FindQuery::find_all()`, tx, `batch_size`, `max_results`)
+    ///
+    /// Check out the `async_batch_read` example for usage details
+    pub async fn get_all_batched<T: TypedCouchDocument>(
+        &self,
+        tx: Sender<DocumentCollection<T>>,
+        batch_size: u64,
+        max_results: u64,
     ) -> CouchResult<u64> {
         let query = FindQuery::find_all();
         self.find_batched(query, tx, batch_size, max_results).await
@@ -525,8 +1142,7 @@ impl Database {
         // to a GET call. It provides the same functionality
         let response = self
             .client
-            .post(view_path, js!(&queries))
-            .send()
+            .send(self.client.post(view_path, js!(&queries)))
             .await?
             .error_for_status()?;
 
@@ -546,6 +1162,15 @@ impl Database {
     pub async fn get_all_params<T: TypedCouchDocument>(
         &self,
         params: Option<QueryParams<DocumentId>>,
+    ) -> CouchResult<DocumentCollection<T>> {
+        self.all_docs_at(&self.create_raw_path("_all_docs"), params).await
+    }
+
+    /// Gets documents through the `_all_docs` endpoint at `path`, which is also used for partitions.
+    pub(crate) async fn all_docs_at<T: TypedCouchDocument>(
+        &self,
+        path: &str,
+        params: Option<QueryParams<DocumentId>>,
     ) -> CouchResult<DocumentCollection<T>> {
         let mut options = params.unwrap_or_default();
 
@@ -555,8 +1180,7 @@ impl Database {
         // to a GET call. It provides the same functionality
         let response = self
             .client
-            .post(&self.create_raw_path("_all_docs"), js!(&options))
-            .send()
+            .send(self.client.post(path, js!(&options)))
             .await?
             .error_for_status()?;
 
@@ -622,8 +1246,16 @@ impl Database {
     /// }
     /// ```
     pub async fn find<T: TypedCouchDocument>(&self, query: &FindQuery) -> CouchResult<DocumentCollection<T>> {
-        let path = self.create_raw_path("_find");
-        let response = self.client.post(&path, js!(query)).send().await?;
+        self.find_at(&self.create_raw_path("_find"), query).await
+    }
+
+    /// Runs a Mango query through the `_find` endpoint at `path`, which is also used for partitions.
+    pub(crate) async fn find_at<T: TypedCouchDocument>(
+        &self,
+        path: &str,
+        query: &FindQuery,
+    ) -> CouchResult<DocumentCollection<T>> {
+        let response = self.client.send(self.client.post(path, js!(query))).await?;
         let status = response.status();
         let data: FindResult<T> = response.couch_json().await?;
 
@@ -705,20 +1337,50 @@ impl Database {
     /// }
     ///```
     pub async fn save<T: TypedCouchDocument>(&self, doc: &mut T) -> DocumentCreatedResult {
+        created_document(self.save_with_options(doc, &WriteOptions::default()).await?)
+    }
+
+    /// Like [`save`](Self::save), with write options. A write in batch mode is accepted without a revision;
+    /// the `_rev` of the document is then left as it is, and [`DocumentWritten::Accepted`] is returned.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::error::CouchResult;
+    /// use couch_rs::types::document::DocumentWritten;
+    /// use couch_rs::types::options::WriteOptions;
+    /// use serde_json::json;
+    ///
+    /// const TEST_DB: &str = "test_db";
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client.db(TEST_DB).await?;
+    ///
+    ///     let mut reading = json!({"_id": "sensor-1-1700000000", "celsius": 21.5});
+    ///     let written = db.save_with_options(&mut reading, &WriteOptions::default().batch(true)).await?;
+    ///     assert_eq!(written, DocumentWritten::Accepted { id: "sensor-1-1700000000".to_string() });
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn save_with_options<T: TypedCouchDocument>(
+        &self,
+        doc: &mut T,
+        options: &WriteOptions,
+    ) -> CouchResult<DocumentWritten> {
         let id = doc.get_id().to_string();
         let body = to_string(&doc)?;
-        let response = self.client.put(&self.create_document_path(&id), body).send().await?;
+        let response = self
+            .client
+            .send(
+                self.client
+                    .put(&self.create_document_path(&id), body)
+                    .query(&options.to_params()),
+            )
+            .await?;
         let status = response.status();
         let data: DocumentCreatedResponse = response.json().await?;
-
-        if let (Some(true), Some(id), Some(rev)) = (data.ok, data.id, data.rev) {
-            doc.set_id(&id);
-            doc.set_rev(&rev);
-            Ok(DocumentCreatedDetails { id, rev })
-        } else {
-            let err = data.error.unwrap_or_else(|| s!("unspecified error"));
-            Err(CouchError::new(err, status))
-        }
+        written_document(doc, status, data)
     }
 
     /// Creates a document from a raw JSON document Value.
@@ -749,22 +1411,160 @@ impl Database {
     /// }
     /// ```
     pub async fn create<T: TypedCouchDocument>(&self, doc: &mut T) -> DocumentCreatedResult {
+        created_document(self.create_with_options(doc, &WriteOptions::default()).await?)
+    }
+
+    /// Like [`create`](Self::create), with write options, see [`save_with_options`](Self::save_with_options).
+    pub async fn create_with_options<T: TypedCouchDocument>(
+        &self,
+        doc: &mut T,
+        options: &WriteOptions,
+    ) -> CouchResult<DocumentWritten> {
         let value = to_create_value(doc)?;
-        let response = self.client.post(&self.name, to_string(&value)?).send().await?;
+        let response = self
+            .client
+            .send(
+                self.client
+                    .post(&self.name, to_string(&value)?)
+                    .query(&options.to_params()),
+            )
+            .await?;
 
         let status = response.status();
         let data: DocumentCreatedResponse = response.json().await?;
+        written_document(doc, status, data)
+    }
 
-        if let Some(true) = data.ok {
-            let id = data.id.ok_or_else(|| CouchError::new(s!("invalid id"), status))?;
-            let rev = data.rev.ok_or_else(|| CouchError::new(s!("invalid rev"), status))?;
+    /// Saves a document together with its attachments in a single request. Attachments are either inlined
+    /// as base64 data in the `_attachments` field, or sent as a `multipart/related` request; see [`AttachmentEncoding`].
+    /// Attachment stubs already present on the document are kept; attachments with the same name are replaced.
+    /// Apart from that, this behaves like [`save`](Self::save).
+    pub async fn save_with_attachments<T: TypedCouchDocument>(
+        &self,
+        doc: &mut T,
+        attachments: &HashMap<String, Attachment>,
+        encoding: AttachmentEncoding,
+    ) -> DocumentCreatedResult {
+        let id = doc.get_id().to_string();
+        let value = to_upsert_value(doc)?;
+        let request = self.attachments_request(
+            Method::PUT,
+            &self.create_document_path(&id),
+            value,
+            attachments,
+            encoding,
+        )?;
+
+        let response = self.client.send(request).await?;
+        let status = response.status();
+        let data: DocumentCreatedResponse = response.json().await?;
+        let details = document_created(data, status)?;
+        doc.set_id(&details.id);
+        doc.set_rev(&details.rev);
+        Ok(details)
+    }
 
-            doc.set_id(&id);
-            doc.set_rev(&rev);
-            Ok(DocumentCreatedDetails { id, rev })
+    /// Creates a document together with its attachments in a single request. `CouchDB` only accepts
+    /// `multipart/related` requests for documents with a known `_id`, so when the document has no `_id`
+    /// the attachments are always sent inline.
+    /// Apart from that, this behaves like [`create`](Self::create).
+    pub async fn create_with_attachments<T: TypedCouchDocument>(
+        &self,
+        doc: &mut T,
+        attachments: &HashMap<String, Attachment>,
+        encoding: AttachmentEncoding,
+    ) -> DocumentCreatedResult {
+        let id = doc.get_id().to_string();
+        let value = to_create_value(doc)?;
+        let request = if id.is_empty() {
+            self.attachments_request(Method::POST, &self.name, value, attachments, AttachmentEncoding::Inline)?
         } else {
-            let err = data.error.unwrap_or_else(|| s!("unspecified error"));
-            Err(CouchError::new(err, status))
+            self.attachments_request(
+                Method::PUT,
+                &self.create_document_path(&id),
+                value,
+                attachments,
+                encoding,
+            )?
+        };
+
+        let response = self.client.send(request).await?;
+        let status = response.status();
+        let data: DocumentCreatedResponse = response.json().await?;
+        let details = document_created(data, status)?;
+        doc.set_id(&details.id);
+        doc.set_rev(&details.rev);
+        Ok(details)
+    }
+
+    fn attachments_request(
+        &self,
+        method: Method,
+        path: &str,
+        mut value: serde_json::Map<String, Value>,
+        attachments: &HashMap<String, Attachment>,
+        encoding: AttachmentEncoding,
+    ) -> CouchResult<RequestBuilder> {
+        let mut stubs = match value.remove(ATTACHMENTS_FIELD) {
+            Some(Value::Object(stubs)) => stubs,
+            _ => serde_json::Map::new(),
+        };
+
+        let mut names: Vec<&String> = attachments.keys().collect();
+        names.sort();
+
+        match encoding {
+            AttachmentEncoding::Inline => {
+                for name in names {
+                    let attachment = &attachments[name];
+                    stubs.insert(
+                        name.clone(),
+                        json!({
+                            "content_type": attachment.content_type,
+                            "data": general_purpose::STANDARD.encode(&attachment.data),
+                        }),
+                    );
+                }
+                value.insert(ATTACHMENTS_FIELD.to_string(), Value::Object(stubs));
+                Ok(self.client.req(method, path, None).body(to_string(&value)?))
+            }
+            AttachmentEncoding::Multipart => {
+                for name in &names {
+                    let attachment = &attachments[*name];
+                    stubs.insert(
+                        (*name).clone(),
+                        json!({
+                            "content_type": attachment.content_type,
+                            "length": attachment.data.len(),
+                            "follows": true,
+                        }),
+                    );
+                }
+                // attachment parts must be sent in the same order as they appear in `_attachments`
+                let attachment_parts: Vec<_> = stubs
+                    .keys()
+                    .filter_map(|name| attachments.get(name))
+                    .map(|attachment| multipart::Part::new(&attachment.content_type, attachment.data.clone()))
+                    .collect();
+                value.insert(ATTACHMENTS_FIELD.to_string(), Value::Object(stubs));
+
+                let mut parts = vec![multipart::Part::new("application/json", to_string(&value)?.into())];
+                parts.extend(attachment_parts);
+
+                let boundary = multipart::new_boundary();
+                let mut headers = HeaderMap::new();
+                headers.insert(
+                    CONTENT_TYPE,
+                    HeaderValue::from_str(&format!("multipart/related; boundary=\"{boundary}\""))
+                        .map_err(|e| CouchError::new(e.to_string(), StatusCode::BAD_REQUEST))?,
+                );
+
+                Ok(self
+                    .client
+                    .req(method, path, None)
+                    .headers(headers)
+                    .body(multipart::build(&boundary, &parts)))
+            }
         }
     }
 
@@ -824,52 +1624,86 @@ impl Database {
         }
     }
 
-    /// Bulk upsert a list of documents.
+    /// Reads a document, modifies it with the given closure and saves it. When the document was changed
+    /// concurrently, the latest version is read and modified again, so no edits are lost; up to
+    /// [`DEFAULT_UPDATE_ATTEMPTS`] times, see [`update_with_attempts`](Self::update_with_attempts).
+    /// The closure decides whether the document is saved or deleted, or left as it is, and may therefore be called
+    /// more than once.
     ///
-    /// This will first fetch the latest rev for each document that does not have a rev set. It
-    /// will then insert all documents into the database.
-    pub async fn bulk_upsert<T: TypedCouchDocument>(&self, docs: &mut [T]) -> CouchResult<Vec<DocumentCreatedResult>> {
-        // First collect all docs that do not have a rev set.
-        let mut docs_without_rev = vec![];
-        for (i, doc) in docs.iter().enumerate() {
-            if doc.get_rev().is_empty() && !doc.get_id().is_empty() {
-                docs_without_rev.push((doc.get_id().to_string(), i));
-            }
-        }
+    /// Usage:
+    /// ```
+    /// use couch_rs::error::CouchResult;
+    /// use couch_rs::types::update::UpdateAction;
+    /// use serde_json::{json, Value};
+    ///
+    /// const TEST_DB: &str = "test_db";
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client.db(TEST_DB).await?;
+    ///
+    ///     let mut doc = json!({"_id": "counter", "count": 0});
+    ///     db.create(&mut doc).await?;
+    ///
+    ///     db.update("counter", |doc: &mut Value| {
+    ///         if doc["count"].as_i64() >= Some(10) {
+    ///             return UpdateAction::Abort;
+    ///         }
+    ///         doc["count"] = json!(doc["count"].as_i64().unwrap_or_default() + 1);
+    ///         UpdateAction::Save
+    ///     })
+    ///     .await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn update<T, F>(&self, id: &str, change: F) -> CouchResult<UpdateResult<T>>
+    where
+        T: TypedCouchDocument,
+        F: FnMut(&mut T) -> UpdateAction,
+    {
+        self.update_with_attempts(id, DEFAULT_UPDATE_ATTEMPTS, change).await
+    }
 
-        // Fetch the latest rev for the docs that do not have a rev set.
-        let ids_without_rev: Vec<String> = docs_without_rev.iter().map(|(id, _)| id.to_string()).collect();
-        let bulk_get = self.get_bulk::<Value>(ids_without_rev).await?;
-        for (req_idx, (sent_id, doc_idx)) in docs_without_rev.iter().enumerate() {
-            let result = bulk_get.get_data().get(req_idx);
-            let rev = match result {
-                Some(doc) if doc.get_id().as_ref() == sent_id => doc.get_rev().to_string(),
-                _ => {
-                    return Err(CouchError::new(
-                        "Response does not match request".to_string(),
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                    ));
-                }
+    /// Like [`update`](Self::update), with the maximum number of attempts. When every attempt runs into a
+    /// concurrent change, the `CONFLICT` error of the last attempt is returned.
+    pub async fn update_with_attempts<T, F>(
+        &self,
+        id: &str,
+        max_attempts: usize,
+        mut change: F,
+    ) -> CouchResult<UpdateResult<T>>
+    where
+        T: TypedCouchDocument,
+        F: FnMut(&mut T) -> UpdateAction,
+    {
+        let mut attempt = 0;
+        loop {
+            attempt += 1;
+            let mut doc: T = self.get(id).await?;
+            let result = match change(&mut doc) {
+                UpdateAction::Abort => return Ok(UpdateResult::Aborted(doc)),
+                UpdateAction::Save => self.save(&mut doc).await.map(|_| UpdateResult::Saved(doc)),
+                UpdateAction::Delete => self.remove_by_id(id, &doc.get_rev()).await.map(UpdateResult::Deleted),
             };
 
-            if let Some(docs) = docs.get_mut(*doc_idx) {
-                docs.set_rev(&rev);
-            } else {
-                // todo: do we need a warning here?
+            match result {
+                Err(err) if err.status() == Some(StatusCode::CONFLICT) && attempt < max_attempts => {}
+                result => return result,
             }
         }
-
-        // Bulk insert the docs, this also updates the revs.
-        let res = self.bulk_docs(docs).await?;
-        Ok(res)
     }
 
-    /// Creates a design with one of more view documents.
+    /// Applies a JSON Patch or JSON Merge Patch to a document and saves it, starting over with the latest
+    /// version on a conflict, like [`update`](Self::update). The `_id` and `_rev` of the document are not
+    /// patched. Returns the saved document, or a [`CouchError::PatchTestFailed`] error when a `test` operation
+    /// fails.
     ///
     /// Usage:
     /// ```
-    /// use couch_rs::types::view::{CouchFunc, CouchViews};
     /// use couch_rs::error::CouchResult;
+    /// use couch_rs::types::patch::{Patch, PatchOperation};
+    /// use serde_json::{json, Value};
     ///
     /// const TEST_DB: &str = "test_db";
     ///
@@ -878,28 +1712,185 @@ impl Database {
     ///     let client = couch_rs::Client::new_local_test()?;
     ///     let db = client.db(TEST_DB).await?;
     ///
-    ///     let couch_func = CouchFunc {
-    ///             map: "function (doc) { if (doc.funny == true) { emit(doc._id, doc.funny); } }".to_string(),
-    ///             reduce: None,
-    ///     };
+    ///     let mut doc = json!({"_id": "order-1", "status": "new", "items": []});
+    ///     db.create(&mut doc).await?;
     ///
-    ///     let couch_views = CouchViews::new("clip_view", couch_func);
-    ///     db.create_view("clip_design", couch_views).await?;
+    ///     let patch = Patch::Json(vec![
+    ///         PatchOperation::Test { path: "/status".to_string(), value: json!("new") },
+    ///         PatchOperation::Add { path: "/items/-".to_string(), value: json!("apple") },
+    ///     ]);
+    ///     let doc: Value = db.patch("order-1", &patch).await?;
+    ///
+    ///     let doc: Value = db.patch("order-1", &Patch::Merge(json!({"status": "paid"}))).await?;
     ///     Ok(())
     /// }
     /// ```
-    pub async fn create_view<T: Into<Value>>(&self, design_name: &str, views: T) -> CouchResult<DesignCreated> {
-        let doc: Value = views.into();
-        let response = self
-            .client
-            .put(&self.create_design_path(design_name), to_string(&doc)?)
-            .send()
+    pub async fn patch<T: TypedCouchDocument>(&self, id: &str, patch: &Patch) -> CouchResult<T> {
+        let mut failure = None;
+        let result = self
+            .update(id, |doc: &mut Value| match patch.apply_to_document(doc) {
+                Ok(()) => UpdateAction::Save,
+                Err(err) => {
+                    failure = Some(err);
+                    UpdateAction::Abort
+                }
+            })
             .await?;
 
-        let response_status = response.status();
-        let result: DesignCreated = response.json().await?;
-
-        if response_status.is_success() {
+        match (result, failure) {
+            (UpdateResult::Saved(doc), _) => to_document(doc),
+            (_, Some(err)) => Err(err),
+            (_, None) => Err(CouchError::new(
+                s!("patch was not applied"),
+                StatusCode::INTERNAL_SERVER_ERROR,
+            )),
+        }
+    }
+
+    /// Applies a patch to each of the given documents and saves them through `_bulk_docs`. Documents that
+    /// were changed concurrently are patched again, like [`patch`](Self::patch). Returns one result per
+    /// document, in the order of the patches.
+    pub async fn bulk_patch(&self, patches: Vec<(DocumentId, Patch)>) -> CouchResult<Vec<DocumentCreatedResult>> {
+        let mut results: Vec<Option<DocumentCreatedResult>> = vec![None; patches.len()];
+        let mut pending: Vec<usize> = (0..patches.len()).collect();
+        let mut attempt = 0;
+        while !pending.is_empty() {
+            attempt += 1;
+            let ids = pending.iter().map(|&i| (patches[i].0.clone(), None)).collect();
+            let current = self.bulk_get::<Value>(ids, false).await?;
+
+            let mut patched = vec![];
+            let mut docs = vec![];
+            for (&i, result) in pending.iter().zip(current) {
+                let (id, patch) = &patches[i];
+                match result {
+                    BulkGetResult::Found(mut doc) => match patch.apply_to_document(&mut doc) {
+                        Ok(()) => {
+                            patched.push(i);
+                            docs.push(doc);
+                        }
+                        Err(err) => results[i] = Some(Err(err)),
+                    },
+                    BulkGetResult::NotFound { .. } | BulkGetResult::Deleted { .. } => {
+                        results[i] = Some(Err(CouchError::new_with_id(
+                            Some(id.clone()),
+                            s!("not_found"),
+                            StatusCode::NOT_FOUND,
+                        )));
+                    }
+                    BulkGetResult::Error(err) => results[i] = Some(Err(err)),
+                }
+            }
+
+            pending.clear();
+            if docs.is_empty() {
+                break;
+            }
+            for (i, result) in patched.into_iter().zip(self.bulk_docs(&mut docs).await?) {
+                match result {
+                    Err(err) if err.status() == Some(StatusCode::CONFLICT) && attempt < DEFAULT_UPDATE_ATTEMPTS => {
+                        pending.push(i);
+                    }
+                    result => results[i] = Some(result),
+                }
+            }
+        }
+
+        Ok(results
+            .into_iter()
+            .map(|result| {
+                result.unwrap_or_else(|| {
+                    Err(CouchError::new(
+                        s!("patch was not applied"),
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    ))
+                })
+            })
+            .collect())
+    }
+
+    /// Bulk upsert a list of documents.
+    ///
+    /// This will first fetch the latest rev for each document that does not have a rev set. It
+    /// will then insert all documents into the database.
+    pub async fn bulk_upsert<T: TypedCouchDocument>(&self, docs: &mut [T]) -> CouchResult<Vec<DocumentCreatedResult>> {
+        // First collect all docs that do not have a rev set.
+        let mut docs_without_rev = vec![];
+        for (i, doc) in docs.iter().enumerate() {
+            if doc.get_rev().is_empty() && !doc.get_id().is_empty() {
+                docs_without_rev.push((doc.get_id().to_string(), i));
+            }
+        }
+
+        // Fetch the latest rev for the docs that do not have a rev set.
+        let ids_without_rev: Vec<String> = docs_without_rev.iter().map(|(id, _)| id.to_string()).collect();
+        let bulk_get = self.get_bulk::<Value>(ids_without_rev).await?;
+        for (req_idx, (sent_id, doc_idx)) in docs_without_rev.iter().enumerate() {
+            let result = bulk_get.get_data().get(req_idx);
+            let rev = match result {
+                Some(doc) if doc.get_id().as_ref() == sent_id => doc.get_rev().to_string(),
+                _ => {
+                    return Err(CouchError::new(
+                        "Response does not match request".to_string(),
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                    ));
+                }
+            };
+
+            if let Some(docs) = docs.get_mut(*doc_idx) {
+                docs.set_rev(&rev);
+            } else {
+                // todo: do we need a warning here?
+            }
+        }
+
+        // Bulk insert the docs, this also updates the revs.
+        let res = self.bulk_docs(docs).await?;
+        Ok(res)
+    }
+
+    /// Creates a design with one of more view documents. An existing design document with the same name is
+    /// replaced.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::types::view::{CouchFunc, CouchViews};
+    /// use couch_rs::error::CouchResult;
+    ///
+    /// const TEST_DB: &str = "test_db";
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client.db(TEST_DB).await?;
+    ///
+    ///     let couch_func = CouchFunc {
+    ///             map: "function (doc) { if (doc.funny == true) { emit(doc._id, doc.funny); } }".to_string(),
+    ///             reduce: None,
+    ///     };
+    ///
+    ///     let couch_views = CouchViews::new("clip_view", couch_func);
+    ///     db.create_view("clip_design", couch_views).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn create_view<T: Into<Value>>(&self, design_name: &str, views: T) -> CouchResult<DesignCreated> {
+        let mut doc: Value = views.into();
+        // replacing an existing design document requires its current revision
+        if doc.get_rev().is_empty() {
+            if let Some(rev) = self.design_rev(design_name).await? {
+                doc.set_rev(&rev);
+            }
+        }
+        let response = self
+            .client
+            .send(self.client.put(&self.create_design_path(design_name), to_string(&doc)?))
+            .await?;
+
+        let response_status = response.status();
+        let result: DesignCreated = response.json().await?;
+
+        if response_status.is_success() {
             Ok(result)
         } else {
             let error_msg = result.error.unwrap_or_else(|| s!("unspecified error"));
@@ -907,6 +1898,96 @@ impl Database {
         }
     }
 
+    /// The current revision of a design document, from the `ETag` of a `HEAD` request; `None` when it does not
+    /// exist.
+    async fn design_rev(&self, design_name: &str) -> CouchResult<Option<String>> {
+        let response = self
+            .client
+            .send(self.client.head(&self.create_design_path(design_name), None))
+            .await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = response.error_for_status()?;
+        Ok(response
+            .headers()
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(|etag| etag.trim_matches('"').to_string()))
+    }
+
+    /// Gets a design document by name, like `reports` for `_design/reports`.
+    pub async fn get_design(&self, design_name: &str) -> CouchResult<DesignDocument> {
+        let value: Value = self
+            .client
+            .send(self.client.get(&self.create_design_path(design_name), None))
+            .await?
+            .error_for_status()?
+            .couch_json()
+            .await?;
+        to_document(value)
+    }
+
+    /// Creates or updates a design document; an update requires the current `_rev`. On success the `_rev` of the
+    /// design document is updated to the new revision.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::error::CouchResult;
+    /// use couch_rs::types::design::DesignDocument;
+    /// use couch_rs::types::view::CouchFunc;
+    ///
+    /// const TEST_DB: &str = "test_db";
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client.db(TEST_DB).await?;
+    ///
+    ///     let mut design = DesignDocument::new("reports");
+    ///     design.views.insert("all".to_string(), CouchFunc::new("function (doc) { emit(doc._id); }", None).into());
+    ///     db.put_design(&mut design).await?;
+    ///
+    ///     // deploy a new version, using the revision that was just written
+    ///     design.updates.insert(
+    ///         "touch".to_string(),
+    ///         "function (doc, req) { doc.touched = true; return [doc, 'ok']; }".to_string(),
+    ///     );
+    ///     db.put_design(&mut design).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn put_design(&self, design: &mut DesignDocument) -> DocumentCreatedResult {
+        let path = self.create_design_path(design.name());
+        let body = to_string(&design)?;
+        let response = self.client.send(self.client.put(&path, body)).await?;
+        let status = response.status();
+        let data: DocumentCreatedResponse = response.json().await?;
+        created_document(written_document(design, status, data)?)
+    }
+
+    /// Deletes a design document by name and revision, together with its view indexes once they are cleaned up.
+    /// Returns the revision of the tombstone.
+    pub async fn delete_design(&self, design_name: &str, rev: &str) -> CouchResult<String> {
+        let id = format!("{DESIGN_PREFIX}{design_name}");
+        self.remove_at(&self.create_design_path(design_name), &id, rev).await
+    }
+
+    /// Lists the design documents of the database through `_design_docs`, including those of Mango indexes. Use
+    /// `include_docs` to get the documents themselves, for example as [`DesignDocument`].
+    pub async fn list_design_docs<T: TypedCouchDocument>(
+        &self,
+        params: Option<QueryParams<DocumentId>>,
+    ) -> CouchResult<ViewCollection<DocumentId, Value, T>> {
+        let params = params.unwrap_or_default();
+        self.client
+            .send(self.client.post(&self.create_raw_path("_design_docs"), js!(&params)))
+            .await?
+            .error_for_status()?
+            .couch_json()
+            .await
+    }
+
     /// Executes a query against a view, returning untyped Values
     pub async fn query_raw(
         &self,
@@ -972,6 +2053,20 @@ impl Database {
         &self,
         design_name: &str,
         view_name: &str,
+        options: Option<QueryParams<K>>,
+    ) -> CouchResult<ViewCollection<K, V, T>> {
+        self.query_at(&self.create_query_view_path(design_name, view_name), options)
+            .await
+    }
+
+    /// Queries the view at `path`, which is also used for partitions.
+    pub(crate) async fn query_at<
+        K: Serialize + DeserializeOwned + PartialEq + Debug + Clone,
+        V: DeserializeOwned,
+        T: TypedCouchDocument,
+    >(
+        &self,
+        path: &str,
         mut options: Option<QueryParams<K>>,
     ) -> CouchResult<ViewCollection<K, V, T>> {
         if options.is_none() {
@@ -979,8 +2074,7 @@ impl Database {
         }
 
         self.client
-            .post(&self.create_query_view_path(design_name, view_name), js!(&options))
-            .send()
+            .send(self.client.post(path, js!(&options)))
             .await?
             .error_for_status()?
             .json()
@@ -1002,8 +2096,10 @@ impl Database {
         };
 
         self.client
-            .put(&self.create_execute_update_path(design_id, name, document_id), body)
-            .send()
+            .send(
+                self.client
+                    .put(&self.create_execute_update_path(design_id, name, document_id), body),
+            )
             .await?
             .error_for_status()?
             .text()
@@ -1011,7 +2107,9 @@ impl Database {
             .map_err(CouchError::from)
     }
 
-    /// Removes a document from the database. Returns success in a `bool`
+    /// Removes a document from the database, by writing a tombstone revision that replicates. Only the `_id` and
+    /// `_rev` of the document are used. Returns the revision of the tombstone; a `CONFLICT` error when the
+    /// revision is outdated, and a `NOT_FOUND` error when the document does not exist.
     /// Usage:
     /// ```
     /// use couch_rs::types::find::FindQuery;
@@ -1029,30 +2127,411 @@ impl Database {
     ///     // first we need to get the document, because we need both the _id and _rev in order
     ///     // to delete
     ///     if let Some(doc) = db.get::<Value>("123").await.ok() {
-    ///         db.remove(&doc).await;
+    ///         let tombstone_rev = db.remove(&doc).await?;
     ///     }
     ///
     ///     Ok(())
     /// }
     ///```
-    pub async fn remove<T: TypedCouchDocument>(&self, doc: &T) -> CouchResult<()> {
-        let mut h = HashMap::new();
-        h.insert(s!("rev"), doc.get_rev().into_owned());
+    pub async fn remove<T: TypedCouchDocument>(&self, doc: &T) -> CouchResult<String> {
+        self.remove_by_id(&doc.get_id(), &doc.get_rev()).await
+    }
+
+    /// Removes the given revision of a document, see [`remove`](Self::remove). Returns the revision of the tombstone.
+    pub async fn remove_by_id(&self, id: &str, rev: &str) -> CouchResult<String> {
+        self.remove_at(&self.create_document_path(id), id, rev).await
+    }
 
-        let request = self.client.delete(&self.create_document_path(&doc.get_id()), Some(&h));
-        match request.send().await {
-            Ok(_) => Ok(()),
-            Err(e) => {
-                let id: String = doc.get_id().into();
-                Err(CouchError::new_with_id(
-                    Some(id),
-                    "Failed to delete document".to_string(),
-                    e.status().unwrap_or(StatusCode::BAD_REQUEST),
-                ))
+    /// Removes the document with the given id and revision at `path`; returns the revision of the tombstone.
+    async fn remove_at(&self, path: &str, id: &str, rev: &str) -> CouchResult<String> {
+        let mut params = HashMap::new();
+        params.insert(s!("rev"), rev.to_string());
+        let response = self.client.send(self.client.delete(path, Some(&params))).await?;
+        let status = response.status();
+        let data: DocumentCreatedResponse = response.json().await?;
+        match (status.is_success(), data.rev) {
+            (true, Some(rev)) => Ok(rev),
+            _ => {
+                let reason = data.reason.or(data.error).unwrap_or_else(|| s!("unspecified error"));
+                Err(CouchError::new_with_id(Some(id.to_string()), reason, status))
             }
         }
     }
 
+    /// Removes documents in bulk, by sending tombstones through `_bulk_docs`. Only the `_id` and `_rev` of the
+    /// documents are used. Returns one result per document, with the revision of its tombstone.
+    pub async fn bulk_remove<T: TypedCouchDocument>(&self, docs: &[T]) -> CouchResult<Vec<DocumentCreatedResult>> {
+        let mut tombstones: Vec<Value> = docs
+            .iter()
+            .map(|doc| json!({ ID_FIELD: doc.get_id(), REV_FIELD: doc.get_rev(), DELETED_FIELD: true }))
+            .collect();
+        self.bulk_docs(&mut tombstones).await
+    }
+
+    /// Permanently removes the given revisions of documents, unlike [`remove`](Self::remove), which leaves a
+    /// tombstone that replicates. Purged revisions are not replicated; purge them on every replica.
+    /// See [_purge](https://docs.couchdb.org/en/stable/api/database/misc.html#db-purge) for more details.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::error::CouchResult;
+    /// use std::collections::HashMap;
+    ///
+    /// const TEST_DB: &str = "test_db";
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client.db(TEST_DB).await?;
+    ///
+    ///     let mut revs = HashMap::new();
+    ///     revs.insert("1234".to_string(), vec!["1-abc".to_string()]);
+    ///     let result = db.purge(revs).await?;
+    ///     println!("purged {:?}", result.purged);
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn purge(&self, revs: RevsByDocument) -> CouchResult<PurgeResult> {
+        self.client
+            .send(self.client.post(&self.create_raw_path("_purge"), to_string(&revs)?))
+            .await?
+            .error_for_status()?
+            .couch_json()
+            .await
+    }
+
+    /// Permanently removes a document, by purging all of its leaf revisions, including conflicts and deletions.
+    pub async fn purge_all_revisions(&self, id: &str) -> CouchResult<PurgeResult> {
+        let revs = self
+            .get_open_revs::<Value>(id, OpenRevs::All)
+            .await?
+            .into_iter()
+            .filter_map(|revision| match revision {
+                OpenRevision::Found(doc) => Some(doc.get_rev().into_owned()),
+                OpenRevision::Deleted(rev) => Some(rev),
+                OpenRevision::Missing(_) => None,
+            })
+            .collect();
+
+        let mut docs = RevsByDocument::new();
+        docs.insert(id.to_string(), revs);
+        self.purge(docs).await
+    }
+
+    /// Gets the maximum number of purges that are remembered, so they can be applied to indexes and replicas.
+    pub async fn purged_infos_limit(&self) -> CouchResult<u64> {
+        self.client
+            .send(self.client.get(&self.create_raw_path("_purged_infos_limit"), None))
+            .await?
+            .error_for_status()?
+            .couch_json()
+            .await
+    }
+
+    /// Sets the maximum number of purges that are remembered, so they can be applied to indexes and replicas.
+    pub async fn set_purged_infos_limit(&self, limit: u64) -> CouchResult<()> {
+        self.client
+            .send(
+                self.client
+                    .put(&self.create_raw_path("_purged_infos_limit"), limit.to_string()),
+            )
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Copies a document on the server, without transferring it. The source revision defaults to the latest one;
+    /// the destination revision is required when the destination document already exists, and is overwritten.
+    /// Returns a `NOT_FOUND` error when the source does not exist, and a `CONFLICT` error when the destination
+    /// exists and its revision is missing or outdated.
+    /// See [COPY](https://docs.couchdb.org/en/stable/api/document/common.html#copy--db-docid) for more details.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::error::CouchResult;
+    /// use serde_json::json;
+    ///
+    /// const TEST_DB: &str = "test_db";
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client.db(TEST_DB).await?;
+    ///
+    ///     let mut template = json!({"_id": "template", "title": "untitled"});
+    ///     db.create(&mut template).await?;
+    ///
+    ///     let details = db.copy("template", None, "report-1", None).await?;
+    ///     assert_eq!(details.id, "report-1");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn copy(
+        &self,
+        source_id: &str,
+        source_rev: Option<&str>,
+        dest_id: &str,
+        dest_rev: Option<&str>,
+    ) -> DocumentCreatedResult {
+        let destination = copy_destination(dest_id, dest_rev);
+        let destination = HeaderValue::from_bytes(destination.as_bytes())
+            .map_err(|_| CouchError::new(format!("invalid destination: {destination}"), StatusCode::BAD_REQUEST))?;
+
+        let mut request = self
+            .client
+            .req(
+                Method::from_bytes(b"COPY").expect("COPY is a valid method"),
+                &self.create_document_path(source_id),
+                None,
+            )
+            .header("Destination", destination);
+        if let Some(rev) = source_rev {
+            request = request.query(&[("rev", rev)]);
+        }
+
+        let response = self.client.send(request).await?;
+        let status = response.status();
+        let data: DocumentCreatedResponse = response.json().await?;
+        if status.is_success() {
+            return data.into();
+        }
+
+        let id = if status == StatusCode::NOT_FOUND {
+            source_id
+        } else {
+            dest_id
+        };
+        let reason = data.reason.or(data.error).unwrap_or_else(|| s!("unspecified error"));
+        Err(CouchError::new_with_id(Some(id.to_string()), reason, status))
+    }
+
+    /// Gets a local document. Local documents are not replicated, and are not listed by `_all_docs` or
+    /// `_changes`; they are typically used for checkpoints. The id may include the `_local/` prefix or not.
+    /// See [local documents](https://docs.couchdb.org/en/stable/api/local.html) for more details.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::error::CouchResult;
+    /// use serde_json::{json, Value};
+    ///
+    /// const TEST_DB: &str = "test_db";
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client.db(TEST_DB).await?;
+    ///
+    ///     let mut checkpoint = json!({"_id": "checkpoint", "seq": "42-abc"});
+    ///     db.save_local(&mut checkpoint).await?;
+    ///     assert_eq!(checkpoint["_id"], "_local/checkpoint");
+    ///
+    ///     let checkpoint: Value = db.get_local("checkpoint").await?;
+    ///     db.remove_local(&checkpoint).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_local<T: TypedCouchDocument>(&self, id: &str) -> CouchResult<T> {
+        self.get(&local_id(id)).await
+    }
+
+    /// Creates or updates a local document; the `_local/` prefix is added to its id when missing.
+    /// Local documents have a revision like `0-1`, that is counted up on every update; like other documents,
+    /// an update requires the current revision.
+    pub async fn save_local<T: TypedCouchDocument>(&self, doc: &mut T) -> DocumentCreatedResult {
+        let id = local_id(&doc.get_id());
+        doc.set_id(&id);
+        self.save(doc).await
+    }
+
+    /// Removes a local document. Unlike other documents, no tombstone is kept.
+    pub async fn remove_local<T: TypedCouchDocument>(&self, doc: &T) -> CouchResult<()> {
+        self.remove_by_id(&local_id(&doc.get_id()), &doc.get_rev())
+            .await
+            .map(|_| ())
+    }
+
+    /// Lists the local documents, with the same parameters as `_all_docs`. Each row has the id of a local
+    /// document as key, and an object with its revision, like `{"rev": "0-1"}`, as value.
+    pub async fn list_local_docs<T: TypedCouchDocument>(
+        &self,
+        params: Option<QueryParams<DocumentId>>,
+    ) -> CouchResult<ViewCollection<DocumentId, Value, T>> {
+        let params = params.unwrap_or_default();
+        self.client
+            .send(self.client.post(&self.create_raw_path("_local_docs"), js!(&params)))
+            .await?
+            .error_for_status()?
+            .couch_json()
+            .await
+    }
+
+    /// Uploads an attachment to a document. The document's `_id` and `_rev` are used to address the document;
+    /// on success the document's `_rev` is updated to the new revision.
+    /// When the document does not exist yet (empty `_rev`), `CouchDB` will create it.
+    /// See [attachments](https://docs.couchdb.org/en/stable/api/document/attachments.html) for more details.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::error::CouchResult;
+    /// use serde_json::json;
+    ///
+    /// const TEST_DB: &str = "test_db";
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client.db(TEST_DB).await?;
+    ///     let mut doc = json!({"_id": "with_attachment"});
+    ///     db.save(&mut doc).await?;
+    ///
+    ///     db.put_attachment(&mut doc, "hello.txt", "text/plain", "hello world").await?;
+    ///     let attachment = db.get_attachment("with_attachment", "hello.txt").await?;
+    ///     assert_eq!(attachment.data, "hello world");
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn put_attachment<T: TypedCouchDocument>(
+        &self,
+        doc: &mut T,
+        name: &str,
+        content_type: &str,
+        data: impl Into<Bytes>,
+    ) -> DocumentCreatedResult {
+        self.put_attachment_body(doc, name, content_type, Body::from(data.into()))
+            .await
+    }
+
+    /// Uploads an attachment to a document, streaming its content from `stream` so it never needs to be
+    /// fully loaded in memory. Apart from that, this behaves like [`put_attachment`](Self::put_attachment).
+    ///
+    /// To upload a file, wrap it in a `tokio_util::io::ReaderStream`.
+    pub async fn put_attachment_stream<T, S>(
+        &self,
+        doc: &mut T,
+        name: &str,
+        content_type: &str,
+        stream: S,
+    ) -> DocumentCreatedResult
+    where
+        T: TypedCouchDocument,
+        S: TryStream + Send + 'static,
+        S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+        Bytes: From<S::Ok>,
+    {
+        self.put_attachment_body(doc, name, content_type, Body::wrap_stream(stream))
+            .await
+    }
+
+    async fn put_attachment_body<T: TypedCouchDocument>(
+        &self,
+        doc: &mut T,
+        name: &str,
+        content_type: &str,
+        body: Body,
+    ) -> DocumentCreatedResult {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            CONTENT_TYPE,
+            HeaderValue::from_str(content_type).map_err(|e| CouchError::new(e.to_string(), StatusCode::BAD_REQUEST))?,
+        );
+
+        let id = doc.get_id().into_owned();
+        let rev = doc.get_rev();
+        let mut request = self
+            .client
+            .req(Method::PUT, &self.create_attachment_path(&id, name), None)
+            .headers(headers)
+            .body(body);
+        if !rev.is_empty() {
+            request = request.query(&[("rev", rev.as_ref())]);
+        }
+
+        let response = self.client.send(request).await?;
+        let status = response.status();
+        let data: DocumentCreatedResponse = response.json().await?;
+        let details = document_created(data, status)?;
+        doc.set_rev(&details.rev);
+        Ok(details)
+    }
+
+    /// Downloads an attachment of a document into memory. Use [`get_attachment_stream`](Self::get_attachment_stream)
+    /// for large attachments.
+    pub async fn get_attachment(&self, id: &str, name: &str) -> CouchResult<Attachment> {
+        let response = self
+            .client
+            .send(self.client.get(&self.create_attachment_path(id, name), None))
+            .await?
+            .error_for_status()?;
+        let (content_type, digest) = attachment_headers(&response);
+        let data = response.bytes().await?;
+
+        Ok(Attachment {
+            content_type,
+            digest,
+            data,
+        })
+    }
+
+    /// Downloads an attachment of a document as a stream of bytes, so large attachments do not have to be
+    /// held in memory.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::error::CouchResult;
+    /// use futures_util::StreamExt;
+    ///
+    /// const TEST_DB: &str = "test_db";
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client.db(TEST_DB).await?;
+    ///
+    ///     let mut stream = db.get_attachment_stream("with_attachment", "hello.txt").await?;
+    ///     while let Some(chunk) = stream.next().await {
+    ///         println!("received {} bytes", chunk?.len());
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn get_attachment_stream(&self, id: &str, name: &str) -> CouchResult<AttachmentStream> {
+        let response = self
+            .client
+            .send(self.client.get(&self.create_attachment_path(id, name), None))
+            .await?
+            .error_for_status()?;
+        let (content_type, digest) = attachment_headers(&response);
+        let content_length = response.content_length();
+        let stream = response.bytes_stream().map_err(CouchError::from);
+
+        Ok(AttachmentStream::new(
+            content_type,
+            digest,
+            content_length,
+            Box::pin(stream),
+        ))
+    }
+
+    /// Deletes an attachment from a document. The document's `_id` and `_rev` are used to address the
+    /// document; on success the document's `_rev` is updated to the new revision.
+    pub async fn delete_attachment<T: TypedCouchDocument>(&self, doc: &mut T, name: &str) -> DocumentCreatedResult {
+        let mut h = HashMap::new();
+        h.insert(s!("rev"), doc.get_rev().into_owned());
+
+        let response = self
+            .client
+            .send(
+                self.client
+                    .delete(&self.create_attachment_path(&doc.get_id(), name), Some(&h)),
+            )
+            .await?;
+        let status = response.status();
+        let data: DocumentCreatedResponse = response.json().await?;
+        let details = document_created(data, status)?;
+        doc.set_rev(&details.rev);
+        Ok(details)
+    }
+
     /// Inserts an index on a database, using the `_index` endpoint.
     ///
     /// Arguments to this function include name, index specification, index type, and the
@@ -1130,8 +2609,10 @@ impl Database {
 
         let response = self
             .client
-            .post(&self.create_raw_path("_index"), js!(Value::Object(body.clone())))
-            .send()
+            .send(
+                self.client
+                    .post(&self.create_raw_path("_index"), js!(Value::Object(body.clone()))),
+            )
             .await?;
 
         let status = response.status();
@@ -1147,8 +2628,7 @@ impl Database {
     /// Reads the database's indexes and returns them
     pub async fn read_indexes(&self) -> CouchResult<DatabaseIndexList> {
         self.client
-            .get(&self.create_raw_path("_index"), None)
-            .send()
+            .send(self.client.get(&self.create_raw_path("_index"), None))
             .await?
             .json()
             .await
@@ -1161,8 +2641,7 @@ impl Database {
 
         match self
             .client
-            .delete(&self.create_raw_path(&uri), None)
-            .send()
+            .send(self.client.delete(&self.create_raw_path(&uri), None))
             .await?
             .json::<DeleteIndexResponse>()
             .await
@@ -1214,25 +2693,302 @@ impl Database {
     pub async fn get_design_info(&self, design_name: &str) -> CouchResult<DesignInfo> {
         let uri = format!("{}/_info", self.create_design_path(design_name));
         self.client
-            .get(&uri, None)
-            .send()
+            .send(self.client.get(&uri, None))
             .await?
             .json::<DesignInfo>()
             .await
             .map_err(CouchError::from)
     }
+
+    /// Gets the security object of the database. A database without a security object returns an empty one.
+    /// See [_security](https://docs.couchdb.org/en/stable/api/database/security.html) for more details.
+    pub async fn get_security(&self) -> CouchResult<SecurityObject> {
+        let response = self
+            .client
+            .send(self.client.get(&self.create_raw_path("_security"), None))
+            .await?
+            .error_for_status()?;
+        response.couch_json().await
+    }
+
+    /// Replaces the security object of the database. Requires admin privileges on the database.
+    ///
+    /// Usage:
+    /// ```
+    /// use couch_rs::{error::CouchResult, types::security::SecurityObject};
+    ///
+    /// const TEST_DB: &str = "test_db";
+    ///
+    /// #[tokio::main]
+    /// async fn main() -> CouchResult<()> {
+    ///     let client = couch_rs::Client::new_local_test()?;
+    ///     let db = client.db(TEST_DB).await?;
+    ///
+    ///     let mut security = SecurityObject::default();
+    ///     security.admins.add_name("tenant_admin");
+    ///     security.members.add_role("tenant");
+    ///     db.set_security(&security).await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    pub async fn set_security(&self, security: &SecurityObject) -> CouchResult<()> {
+        self.client
+            .send(
+                self.client
+                    .put(&self.create_raw_path("_security"), to_string(security)?),
+            )
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+
+    /// Adds a role to the members of the database. Returns the updated security object.
+    ///
+    /// The security object has no revisions, so concurrent edits can be lost: an edit that another client writes
+    /// between reading and writing the security object is overwritten, and this can not be detected. The security
+    /// object is read back after writing it; when it differs from what was written, a later edit may have
+    /// overwritten this change, and a `CONFLICT` error is returned. Coordinate security changes outside of `CouchDB`
+    /// when concurrent edits must not be lost.
+    pub async fn add_member_role(&self, role: &str) -> CouchResult<SecurityObject> {
+        self.update_security(|security| security.members.add_role(role)).await
+    }
+
+    /// Removes a role from the members of the database. Returns the updated security object.
+    /// See [`add_member_role`](Self::add_member_role) for how concurrent edits are dealt with.
+    pub async fn remove_member_role(&self, role: &str) -> CouchResult<SecurityObject> {
+        self.update_security(|security| security.members.remove_role(role))
+            .await
+    }
+
+    /// Applies a change to the latest security object and reads it back. Concurrent edits made between the read
+    /// and the write are lost. The change returns false when the security object is already up to date.
+    async fn update_security(
+        &self,
+        change: impl FnOnce(&mut SecurityObject) -> bool + Send,
+    ) -> CouchResult<SecurityObject> {
+        let mut security = self.get_security().await?;
+        if !change(&mut security) {
+            return Ok(security);
+        }
+        self.set_security(&security).await?;
+        let written = self.get_security().await?;
+        if written != security {
+            return Err(CouchError::new(
+                s!("the security object was modified concurrently"),
+                StatusCode::CONFLICT,
+            ));
+        }
+        Ok(written)
+    }
+}
+
+/// How many times [`Database::update`] reads and modifies a document, before giving up on concurrent edits.
+pub const DEFAULT_UPDATE_ATTEMPTS: usize = 10;
+
+/// The maximum number of revisions sent in a single `_revs_diff` or `_missing_revs` request.
+const REVS_CHUNK_SIZE: usize = 1000;
+
+/// The `Destination` header of a `COPY` request. `CouchDB` splits it on `?` and decodes the id, so the id is
+/// percent-encoded, but the `?rev=` suffix is not.
+fn copy_destination(dest_id: &str, dest_rev: Option<&str>) -> String {
+    let encoded = utf8_percent_encode(dest_id, PATH_SEGMENT);
+    match dest_rev {
+        Some(rev) => format!("{encoded}?rev={rev}"),
+        None => encoded.to_string(),
+    }
+}
+
+fn local_id(id: &str) -> String {
+    if id.starts_with(LOCAL_PREFIX) {
+        id.to_string()
+    } else {
+        format!("{LOCAL_PREFIX}{id}")
+    }
+}
+
+fn take_field<T: DeserializeOwned>(value: &mut Value, field: &str) -> CouchResult<Option<T>> {
+    match value.as_object_mut().and_then(|doc| doc.remove(field)) {
+        Some(field) => Ok(Some(from_value(field)?)),
+        None => Ok(None),
+    }
+}
+
+/// Parses an entry of a JSON `open_revs` response: `{"ok": doc}` or `{"missing": rev}`.
+fn open_revision_from_json<T: TypedCouchDocument>(mut value: Value) -> CouchResult<OpenRevision<T>> {
+    if let Some(missing) = value.get("missing").and_then(Value::as_str) {
+        return Ok(OpenRevision::Missing(missing.to_string()));
+    }
+    match value.get_mut("ok") {
+        Some(doc) => open_revision(doc.take()),
+        None => Err(CouchError::new(
+            format!("unexpected open_revs entry: {value}"),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        )),
+    }
+}
+
+/// Parses a part of a multipart `open_revs` response: a document, a `{"missing": rev}` object, or a
+/// `multipart/related` document with its attachments.
+fn open_revision_from_part<T: TypedCouchDocument>(part: &multipart::Part) -> CouchResult<OpenRevision<T>> {
+    let content_type = part.content_type().unwrap_or_default();
+    let value: Value = match multipart_boundary(content_type) {
+        Some(boundary) => parse_document_with_attachments(&multipart::parse(&part.body, &boundary)?)?.0,
+        None => serde_json::from_slice(&part.body)?,
+    };
+
+    match value.get("missing").and_then(Value::as_str) {
+        Some(missing) if value.get(ID_FIELD).is_none() => Ok(OpenRevision::Missing(missing.to_string())),
+        _ => open_revision(value),
+    }
+}
+
+fn open_revision<T: TypedCouchDocument>(doc: Value) -> CouchResult<OpenRevision<T>> {
+    if doc.get(DELETED_FIELD) == Some(&Value::Bool(true)) {
+        Ok(OpenRevision::Deleted(get_mandatory_string_value(REV_FIELD, &doc)?))
+    } else {
+        Ok(OpenRevision::Found(to_document(doc)?))
+    }
 }
 
-fn get_mandatory_string_value(key: &str, value: &Value) -> CouchResult<String> {
-    let id = if let Some(Value::String(id)) = value.get(key) {
-        id.to_owned()
+fn multipart_boundary(content_type: &str) -> Option<String> {
+    if content_type.starts_with("multipart/") {
+        multipart::boundary(content_type)
     } else {
+        None
+    }
+}
+
+/// Splits a `multipart/related` document response into the document and its attachments.
+fn parse_document_with_attachments(parts: &[multipart::Part]) -> CouchResult<(Value, HashMap<String, Attachment>)> {
+    let Some((doc_part, attachment_parts)) = parts.split_first() else {
         return Err(CouchError::new(
-            format!("No {key} found in returned data: {value:?}"),
+            s!("multipart response does not contain a document"),
             StatusCode::INTERNAL_SERVER_ERROR,
         ));
     };
-    Ok(id)
+
+    let value: Value = serde_json::from_slice(&doc_part.body)?;
+    let stubs = value.get(ATTACHMENTS_FIELD).and_then(Value::as_object);
+    // parts without a filename follow the order of the `_attachments` stubs that are marked as `follows`
+    let mut following = stubs
+        .map(|stubs| {
+            stubs
+                .iter()
+                .filter(|(_, stub)| stub.get("follows") == Some(&Value::Bool(true)))
+                .map(|(name, _)| name.clone())
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default()
+        .into_iter();
+
+    let mut attachments = HashMap::new();
+    for part in attachment_parts {
+        let Some(name) = part.filename().or_else(|| following.next()) else {
+            continue;
+        };
+        let stub = stubs.and_then(|stubs| stubs.get(&name));
+        let content_type = part
+            .content_type()
+            .map(ToString::to_string)
+            .or_else(|| stub_string(stub, "content_type"))
+            .unwrap_or_else(|| s!("application/octet-stream"));
+
+        attachments.insert(
+            name,
+            Attachment {
+                content_type,
+                digest: stub_string(stub, "digest"),
+                data: part.body.clone(),
+            },
+        );
+    }
+
+    Ok((value, attachments))
+}
+
+/// Decodes the base64 attachments that are inlined in the `_attachments` field of a document.
+fn inline_attachments(value: &Value) -> CouchResult<HashMap<String, Attachment>> {
+    let mut attachments = HashMap::new();
+    let Some(stubs) = value.get(ATTACHMENTS_FIELD).and_then(Value::as_object) else {
+        return Ok(attachments);
+    };
+
+    for (name, stub) in stubs {
+        let Some(Value::String(data)) = stub.get("data") else {
+            continue;
+        };
+        let data = general_purpose::STANDARD
+            .decode(data)
+            .map_err(|e| CouchError::new(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR))?;
+        attachments.insert(
+            name.clone(),
+            Attachment {
+                content_type: stub_string(Some(stub), "content_type").unwrap_or_else(|| s!("application/octet-stream")),
+                digest: stub_string(Some(stub), "digest"),
+                data: data.into(),
+            },
+        );
+    }
+
+    Ok(attachments)
+}
+
+fn stub_string(stub: Option<&Value>, field: &str) -> Option<String> {
+    stub.and_then(|s| s.get(field))
+        .and_then(Value::as_str)
+        .map(ToString::to_string)
+}
+
+fn document_created(data: DocumentCreatedResponse, status: StatusCode) -> DocumentCreatedResult {
+    if let (Some(true), Some(id), Some(rev)) = (data.ok, data.id, data.rev) {
+        Ok(DocumentCreatedDetails { id, rev })
+    } else {
+        let err = data.error.unwrap_or_else(|| s!("unspecified error"));
+        Err(CouchError::new(err, status))
+    }
+}
+
+fn attachment_headers(response: &Response) -> (String, Option<String>) {
+    let headers = response.headers();
+    let content_type = headers
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("application/octet-stream")
+        .to_string();
+    let digest = headers.get(ETAG).and_then(|v| v.to_str().ok()).map(digest_from_etag);
+    (content_type, digest)
+}
+
+/// Updates the document with the outcome of a single document write. A write that is accepted in batch mode has
+/// no revision yet, so the revision of the document is left as it is.
+fn written_document<T: TypedCouchDocument>(
+    doc: &mut T,
+    status: StatusCode,
+    data: DocumentCreatedResponse,
+) -> CouchResult<DocumentWritten> {
+    if data.ok != Some(true) {
+        let err = data.error.unwrap_or_else(|| s!("unspecified error"));
+        return Err(CouchError::new(err, status));
+    }
+
+    let id = data.id.ok_or_else(|| CouchError::new(s!("invalid id"), status))?;
+    doc.set_id(&id);
+    match data.rev {
+        Some(rev) => {
+            doc.set_rev(&rev);
+            Ok(DocumentWritten::Created(DocumentCreatedDetails { id, rev }))
+        }
+        None if status == StatusCode::ACCEPTED => Ok(DocumentWritten::Accepted { id }),
+        None => Err(CouchError::new(s!("invalid rev"), status)),
+    }
+}
+
+/// Only writes in batch mode are accepted without a revision.
+fn created_document(written: DocumentWritten) -> DocumentCreatedResult {
+    match written {
+        DocumentWritten::Created(details) => Ok(details),
+        DocumentWritten::Accepted { .. } => Err(CouchError::new(s!("invalid rev"), StatusCode::ACCEPTED)),
+    }
 }
 
 fn to_create_value(doc: &impl TypedCouchDocument) -> CouchResult<serde_json::Map<String, Value>> {
@@ -1292,6 +3048,127 @@ mod tests {
         assert_eq!(p, "testdb/_design/design1/_update/update1/123");
         let p = db.create_compact_path("view1");
         assert_eq!(p, "testdb/_compact/view1");
+        let p = db.create_document_path("_local/1+3");
+        assert_eq!(p, "testdb/_local/1%2B3");
+        let p = db.create_attachment_path("1+3", "photo 1.jpg");
+        assert_eq!(p, "testdb/1%2B3/photo%201.jpg");
+    }
+
+    #[test]
+    fn test_copy_destination() {
+        assert_eq!(copy_destination("report-1", None), "report-1");
+        assert_eq!(copy_destination("a?b%c+d é", None), "a%3Fb%25c%2Bd%20%C3%A9");
+        assert_eq!(copy_destination("a?b", Some("2-abc")), "a%3Fb?rev=2-abc");
+    }
+
+    #[test]
+    fn test_parse_document_with_attachments() {
+        let doc = json!({
+            "_id": "1",
+            "_rev": "1-abc",
+            "_attachments": {
+                "a.txt": {"content_type": "text/plain", "digest": "md5-a", "follows": true},
+                "b.bin": {"content_type": "application/octet-stream", "digest": "md5-b", "follows": true}
+            }
+        });
+        let mut named = multipart::Part::new("text/plain", Bytes::from_static(b"aaa"));
+        named
+            .headers
+            .insert(s!("content-disposition"), s!(r#"attachment; filename="a.txt""#));
+        let parts = vec![
+            multipart::Part::new("application/json", to_string(&doc).unwrap().into()),
+            named,
+        ];
+
+        let (value, attachments) = parse_document_with_attachments(&parts).unwrap();
+        assert_eq!(value["_id"], "1");
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments["a.txt"].data, "aaa");
+        assert_eq!(attachments["a.txt"].digest.as_deref(), Some("md5-a"));
+    }
+
+    #[test]
+    fn test_multipart_attachments_follow_the_json_order() {
+        let client = Client::new_local_test().unwrap();
+        let db = Database::new("testdb".to_string(), client);
+        let mut value = serde_json::Map::new();
+        value.insert(
+            ATTACHMENTS_FIELD.to_string(),
+            json!({"kept.txt": {"content_type": "text/plain", "stub": true}}),
+        );
+        let attachments = HashMap::from([
+            (s!("z.txt"), Attachment::new("text/plain", "zzz")),
+            (s!("a.txt"), Attachment::new("text/plain", "aaa")),
+            (s!("m.txt"), Attachment::new("text/plain", "mmm")),
+        ]);
+
+        let request = db
+            .attachments_request(
+                Method::PUT,
+                "testdb/1",
+                value,
+                &attachments,
+                AttachmentEncoding::Multipart,
+            )
+            .unwrap()
+            .build()
+            .unwrap();
+        let content_type = request.headers()[CONTENT_TYPE].to_str().unwrap();
+        let boundary = multipart::boundary(content_type).unwrap();
+        let body = Bytes::copy_from_slice(request.body().unwrap().as_bytes().unwrap());
+        let parts = multipart::parse(&body, &boundary).unwrap();
+
+        let doc: Value = serde_json::from_slice(&parts[0].body).unwrap();
+        let following: Vec<_> = doc["_attachments"]
+            .as_object()
+            .unwrap()
+            .iter()
+            .filter(|(_, stub)| stub["follows"] == true)
+            .map(|(name, _)| attachments[name].data.clone())
+            .collect();
+        let bodies: Vec<_> = parts[1..].iter().map(|part| part.body.clone()).collect();
+        assert_eq!(bodies, following);
+    }
+
+    #[test]
+    fn test_inline_attachments() {
+        let doc = json!({
+            "_id": "1",
+            "_attachments": {
+                "a.txt": {"content_type": "text/plain", "data": "aGVsbG8="},
+                "stub.txt": {"content_type": "text/plain", "stub": true}
+            }
+        });
+
+        let attachments = inline_attachments(&doc).unwrap();
+        assert_eq!(attachments.len(), 1);
+        assert_eq!(attachments["a.txt"].data, "hello");
+        assert_eq!(attachments["a.txt"].content_type, "text/plain");
+    }
+
+    #[test]
+    fn test_parse_open_revisions() {
+        let found = multipart::Part::new(
+            "application/json",
+            Bytes::from_static(br#"{"_id":"1","_rev":"2-b","v":1}"#),
+        );
+        let deleted = multipart::Part::new(
+            "application/json",
+            Bytes::from_static(br#"{"_id":"1","_rev":"2-c","_deleted":true}"#),
+        );
+        let missing = multipart::Part::new("application/json", Bytes::from_static(br#"{"missing":"3-d"}"#));
+
+        let found: OpenRevision<Value> = open_revision_from_part(&found).unwrap();
+        assert!(matches!(found, OpenRevision::Found(doc) if doc["v"] == 1));
+        let deleted: OpenRevision<Value> = open_revision_from_part(&deleted).unwrap();
+        assert_eq!(deleted, OpenRevision::Deleted(s!("2-c")));
+        let missing: OpenRevision<Value> = open_revision_from_part(&missing).unwrap();
+        assert_eq!(missing, OpenRevision::Missing(s!("3-d")));
+
+        let found: OpenRevision<Value> = open_revision_from_json(json!({"ok": {"_id": "1", "_rev": "2-b"}})).unwrap();
+        assert!(matches!(found, OpenRevision::Found(doc) if doc["_rev"] == "2-b"));
+        let missing: OpenRevision<Value> = open_revision_from_json(json!({"missing": "3-d"})).unwrap();
+        assert_eq!(missing, OpenRevision::Missing(s!("3-d")));
     }
 
     fn build_json_response(body: &'static str) -> Response {
@@ -1,3 +1,5 @@
+use crate::error::{CouchError, CouchResult};
+use reqwest::StatusCode;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 use std::{
@@ -7,6 +9,17 @@ use std::{
 
 pub const ID_FIELD: &str = "_id";
 pub const REV_FIELD: &str = "_rev";
+pub const ATTACHMENTS_FIELD: &str = "_attachments";
+pub const CONFLICTS_FIELD: &str = "_conflicts";
+pub const DELETED_FIELD: &str = "_deleted";
+pub const DELETED_CONFLICTS_FIELD: &str = "_deleted_conflicts";
+pub const LOCAL_SEQ_FIELD: &str = "_local_seq";
+pub const REVISIONS_FIELD: &str = "_revisions";
+pub const REVS_INFO_FIELD: &str = "_revs_info";
+/// Prefix of the ids of local documents, which are not replicated.
+pub const LOCAL_PREFIX: &str = "_local/";
+/// Prefix of the ids of design documents.
+pub const DESIGN_PREFIX: &str = "_design/";
 
 /// Trait to deal with typed `CouchDB` documents.
 ///
@@ -15,9 +28,9 @@ pub const REV_FIELD: &str = "_rev";
 /// because `set_rev` will be called before returning the document to the user, so the user will always see the correct value.
 pub trait TypedCouchDocument: DeserializeOwned + Serialize + Sized {
     /// get the _id field
-    fn get_id(&self) -> Cow<str>;
+    fn get_id(&self) -> Cow<'_, str>;
     /// get the _rev field
-    fn get_rev(&self) -> Cow<str>;
+    fn get_rev(&self) -> Cow<'_, str>;
     /// set the _rev field
     fn set_rev(&mut self, rev: &str);
     /// set the _id field
@@ -28,12 +41,12 @@ pub trait TypedCouchDocument: DeserializeOwned + Serialize + Sized {
 
 /// Allows dealing with _id and _rev fields in untyped (Value) documents
 impl TypedCouchDocument for Value {
-    fn get_id(&self) -> Cow<str> {
+    fn get_id(&self) -> Cow<'_, str> {
         let id: String = json_extr!(self[ID_FIELD]);
         Cow::from(id)
     }
 
-    fn get_rev(&self) -> Cow<str> {
+    fn get_rev(&self) -> Cow<'_, str> {
         let rev: String = json_extr!(self[REV_FIELD]);
         Cow::from(rev)
     }
@@ -185,6 +198,28 @@ impl<T: TypedCouchDocument> IndexMut<usize> for DocumentCollection<T> {
     }
 }
 
+/// Converts a document returned by `CouchDB`, which must have an `_id` and a `_rev`.
+pub(crate) fn to_document<T: TypedCouchDocument>(value: Value) -> CouchResult<T> {
+    let id = get_mandatory_string_value(ID_FIELD, &value)?;
+    let rev = get_mandatory_string_value(REV_FIELD, &value)?;
+    let mut document: T = serde_json::from_value(value)?;
+    document.set_id(&id);
+    document.set_rev(&rev);
+    Ok(document)
+}
+
+pub(crate) fn get_mandatory_string_value(key: &str, value: &Value) -> CouchResult<String> {
+    let id = if let Some(Value::String(id)) = value.get(key) {
+        id.to_owned()
+    } else {
+        return Err(CouchError::new(
+            format!("No {key} found in returned data: {value:?}"),
+            StatusCode::INTERNAL_SERVER_ERROR,
+        ));
+    };
+    Ok(id)
+}
+
 #[cfg(test)]
 mod tests {
     use crate as couch_rs;
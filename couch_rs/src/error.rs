@@ -13,6 +13,8 @@ pub enum CouchError {
     MalformedUrl(ErrorMessage),
     /// A design document could not be created.
     CreateDesignFailed(ErrorMessage),
+    /// A `test` operation of a JSON Patch failed, because the document does not have the expected value.
+    PatchTestFailed(ErrorMessage),
 }
 
 #[derive(Debug, Clone)]
@@ -101,7 +103,10 @@ impl fmt::Display for CouchError {
                     write!(f, "{}: {}", details.status, details.message)
                 }
             }
-            CouchError::InvalidJson(err) | CouchError::MalformedUrl(err) | CouchError::CreateDesignFailed(err) => {
+            CouchError::InvalidJson(err)
+            | CouchError::MalformedUrl(err)
+            | CouchError::CreateDesignFailed(err)
+            | CouchError::PatchTestFailed(err) => {
                 write!(f, "{}", err.message)
             }
         }
@@ -114,9 +119,10 @@ impl error::Error for CouchError {
         // Generic error, underlying cause isn't tracked.
         match self {
             CouchError::OperationFailed(details) => details.upstream.as_ref().map(|e| &**e as _),
-            CouchError::InvalidJson(err) | CouchError::MalformedUrl(err) | CouchError::CreateDesignFailed(err) => {
-                err.upstream.as_ref().map(|e| &**e as _)
-            }
+            CouchError::InvalidJson(err)
+            | CouchError::MalformedUrl(err)
+            | CouchError::CreateDesignFailed(err)
+            | CouchError::PatchTestFailed(err) => err.upstream.as_ref().map(|e| &**e as _),
         }
     }
 }
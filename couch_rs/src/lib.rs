@@ -189,6 +189,8 @@ mod macros {
     }
 }
 
+/// Authentication methods supported by the `Client`.
+pub mod auth;
 mod client;
 /// Database operations on a `CouchDB` Database.
 pub mod database;
@@ -205,12 +207,17 @@ pub mod management;
 /// Trait that provides methods that can be used to switch between abstract Document and
 /// concrete Model implementors (such as your custom data models)
 pub mod model;
+/// Queries on a partition of a partitioned `CouchDB` Database.
+pub mod partition;
+/// Retrying of requests that failed due to a transient error.
+pub mod retry;
 /// Data types to support `CouchDB` operations.
 pub mod types;
 
 mod changes;
+mod multipart;
 
-pub use client::Client;
+pub use client::{Client, ClientBuilder};
 
 #[allow(unused_mut, unused_variables)]
 #[cfg(feature = "integration-tests")]
@@ -239,11 +246,11 @@ mod couch_rs_tests {
         last_name: String,
     }
     impl TypedCouchDocument for TestDocImplementing {
-        fn get_id(&self) -> Cow<str> {
+        fn get_id(&self) -> Cow<'_, str> {
             Cow::Borrowed(&self.my_id)
         }
 
-        fn get_rev(&self) -> Cow<str> {
+        fn get_rev(&self) -> Cow<'_, str> {
             Cow::Borrowed(&self.my_rev)
         }
 
@@ -262,10 +269,16 @@ mod couch_rs_tests {
 
     mod client_tests {
         use crate::{
+            auth::BasicAuth,
             client::Client,
             couch_rs_tests::{TestDoc, TestDocImplementing},
             document::TypedCouchDocument,
             error::CouchError,
+            types::{
+                options::CreateDbOptions,
+                replication::{ReplicationAuth, ReplicationDocument, ReplicationEndpoint, ReplicationOptions},
+                user::CouchUser,
+            },
         };
         use reqwest::StatusCode;
         use serde_json::json;
@@ -327,6 +340,63 @@ mod couch_rs_tests {
             assert!(!client.exists(dbname).await.unwrap());
         }
 
+        #[tokio::test]
+        async fn should_open_existing_db_only() {
+            let client = Client::new_local_test().unwrap();
+            let dbname = "should_open_existing_db_only";
+            let err = client.open_db(dbname).await.unwrap_err();
+            assert!(err.is_not_found());
+            assert!(!client.exists(dbname).await.unwrap());
+
+            client.make_db(dbname).await.expect("can not create db");
+            let db = client.open_db(dbname).await.expect("can not open db");
+            assert_eq!(db.name(), dbname);
+            client.destroy_db(dbname).await.expect("can not destroy db");
+        }
+
+        #[tokio::test]
+        async fn should_not_create_db_when_unauthorized() {
+            let client = Client::new_local_test().unwrap();
+            let unauthorized = Client::new("http://localhost:5984", "admin", "wrong").unwrap();
+            let dbname = "should_not_create_db_when_unauthorized";
+            let err = unauthorized.db(dbname).await.unwrap_err();
+            assert_eq!(err.status(), Some(StatusCode::UNAUTHORIZED));
+            assert!(!client.exists(dbname).await.unwrap());
+        }
+
+        #[tokio::test]
+        async fn should_create_db_with_options() {
+            let client = Client::new_local_test().unwrap();
+            let dbname = "should_create_db_with_options";
+            let options = CreateDbOptions {
+                q: Some(2),
+                n: Some(1),
+                partitioned: false,
+            };
+            client
+                .create_db_with(dbname, &options)
+                .await
+                .expect("can not create db");
+            let info = client.get_info(dbname).await.expect("can not get db info");
+            assert_eq!(info.cluster.q, 2);
+            assert_eq!(info.cluster.n, 1);
+
+            let err = client.create_db_with(dbname, &options).await.unwrap_err();
+            assert_eq!(err.status(), Some(StatusCode::PRECONDITION_FAILED));
+            // make_db accepts an existing database, as long as the options match
+            client.make_db(dbname).await.expect("can not make existing db");
+            client
+                .make_db_with_options(dbname, &options)
+                .await
+                .expect("can not make existing db with the same options");
+            let err = client
+                .make_db_with_options(dbname, &CreateDbOptions::default().partitioned(true))
+                .await
+                .unwrap_err();
+            assert_eq!(err.status(), Some(StatusCode::PRECONDITION_FAILED));
+            client.destroy_db(dbname).await.expect("can not destroy db");
+        }
+
         #[tokio::test]
         async fn should_create_a_document() {
             let client = Client::new_local_test().unwrap();
@@ -350,6 +420,152 @@ mod couch_rs_tests {
                 .expect("can not destroy db");
         }
 
+        #[tokio::test]
+        async fn should_use_a_cookie_session() {
+            let client = Client::new_with_session("http://localhost:5984", "admin", "password").unwrap();
+            let dbname = "should_use_a_cookie_session";
+            let db = client.db(dbname).await.expect("can not create db with a session");
+
+            let mut doc = json!({ "thing": true });
+            db.create(&mut doc)
+                .await
+                .expect("can not create document with a session");
+            assert!(client.list_dbs().await.unwrap().contains(&dbname.to_string()));
+
+            client.destroy_db(dbname).await.expect("can not destroy db");
+        }
+
+        #[tokio::test]
+        async fn should_reject_an_invalid_session() {
+            let client = Client::new_with_session("http://localhost:5984", "admin", "wrong").unwrap();
+            let err = client.list_dbs().await.expect_err("should not start a session");
+            assert_eq!(err.status(), Some(StatusCode::UNAUTHORIZED));
+        }
+
+        #[tokio::test]
+        async fn should_use_a_custom_authenticator() {
+            let client =
+                Client::new_with_auth("http://localhost:5984", BasicAuth::new("admin", Some("password"))).unwrap();
+            assert!(client.list_dbs().await.is_ok());
+
+            let client =
+                Client::new_with_auth("http://localhost:5984", BasicAuth::new("admin", Some("wrong"))).unwrap();
+            let err = client.list_dbs().await.expect_err("should reject the credentials");
+            assert_eq!(err.status(), Some(StatusCode::UNAUTHORIZED));
+        }
+
+        #[tokio::test]
+        async fn should_replicate_a_database() {
+            let client = Client::new_local_test().unwrap();
+            let source = client.db("should_replicate_a_database_source").await.unwrap();
+            let mut doc = json!({ "_id": "replicated", "thing": true });
+            source.save(&mut doc).await.unwrap();
+
+            let result = client
+                .replicate(
+                    ReplicationEndpoint::new("http://localhost:5984/should_replicate_a_database_source")
+                        .auth(ReplicationAuth::basic("admin", "password")),
+                    ReplicationEndpoint::new("http://localhost:5984/should_replicate_a_database_target")
+                        .auth(ReplicationAuth::basic("admin", "password")),
+                    ReplicationOptions::default().create_target(true),
+                )
+                .await
+                .expect("can not replicate");
+            assert!(result.ok);
+
+            let target = client.db("should_replicate_a_database_target").await.unwrap();
+            assert!(target.exists("replicated").await);
+
+            client.destroy_db("should_replicate_a_database_source").await.unwrap();
+            client.destroy_db("should_replicate_a_database_target").await.unwrap();
+        }
+
+        #[tokio::test]
+        async fn should_manage_replication_documents() {
+            let client = Client::new_local_test().unwrap();
+            client.db("_replicator").await.expect("can not create _replicator");
+
+            let mut replication = ReplicationDocument::new(
+                "should_manage_replication_documents",
+                "http://localhost:5984/should_manage_replication_documents_a",
+                "http://localhost:5984/should_manage_replication_documents_b",
+                ReplicationOptions::default().continuous(true),
+            );
+            client
+                .save_replication(&mut replication)
+                .await
+                .expect("can not save replication");
+            assert!(replication.rev.starts_with("1-"));
+
+            let stored = client
+                .get_replication("should_manage_replication_documents")
+                .await
+                .expect("can not get replication");
+            assert_eq!(stored.options.continuous, Some(true));
+            assert!(client
+                .list_replications()
+                .await
+                .unwrap()
+                .iter()
+                .any(|r| r.id == "should_manage_replication_documents"));
+
+            client
+                .delete_replication(&stored)
+                .await
+                .expect("can not delete replication");
+            assert!(client
+                .get_replication("should_manage_replication_documents")
+                .await
+                .is_err());
+        }
+
+        #[tokio::test]
+        async fn should_list_tasks_and_scheduler_state() {
+            let client = Client::new_local_test().unwrap();
+            assert!(client.active_tasks().await.is_ok());
+
+            let jobs = client.scheduler_jobs(Some(10), None).await.expect("can not list jobs");
+            assert!(jobs.jobs.len() <= 10);
+            assert!(client.scheduler_docs(None, None).await.is_ok());
+        }
+
+        #[tokio::test]
+        async fn should_manage_users() {
+            let client = Client::new_local_test().unwrap();
+            client.db("_users").await.expect("can not create _users");
+            let name = "should_manage_users";
+            if let Ok(user) = client.get_user(name).await {
+                client.delete_user(&user).await.unwrap();
+            }
+
+            let mut user = CouchUser::new(name, "apple", vec!["tenant".to_string()]);
+            client.create_user(&mut user).await.expect("can not create user");
+            assert_eq!(user.id, "org.couchdb.user:should_manage_users");
+
+            let mut stored = client.get_user(name).await.expect("can not get user");
+            assert_eq!(stored.roles, vec!["tenant"]);
+            assert!(stored.derived_key.is_some());
+
+            stored.roles.push("reader".to_string());
+            client.update_user(&mut stored).await.expect("can not update user");
+            let as_user = Client::new("http://localhost:5984", name, "apple").unwrap();
+            assert!(as_user.get_user(name).await.is_ok());
+
+            client
+                .change_password(name, "orange")
+                .await
+                .expect("can not change password");
+            let as_user = Client::new("http://localhost:5984", name, "orange").unwrap();
+            let user = as_user
+                .get_user(name)
+                .await
+                .expect("can not log in with the new password");
+            assert_eq!(user.roles, vec!["tenant", "reader"]);
+
+            client.delete_user(&user).await.expect("can not delete user");
+            assert!(client.get_user(name).await.is_err());
+        }
+
         #[tokio::test]
         async fn should_create_a_typed_document() {
             let client = Client::new_local_test().unwrap();
@@ -565,16 +781,25 @@ mod couch_rs_tests {
             client::Client,
             database::Database,
             document::{DocumentCollection, TypedCouchDocument},
-            error::{CouchResult, CouchResultExt},
+            error::{CouchError, CouchResult, CouchResultExt},
             management::{ClusterSetup, EnsureDbsExist},
             types,
             types::{
+                attachment::{Attachment, AttachmentEncoding},
+                design::{DesignDocument, DesignOptions},
+                document::DocumentWritten,
                 find::FindQuery,
+                options::{CreateDbOptions, GetOptions, WriteOptions},
+                patch::{Patch, PatchOperation},
                 query::{QueriesParams, QueryParams},
+                revisions::{OpenRevision, OpenRevs, RevisionStatus},
+                update::{UpdateAction, UpdateResult},
                 view::{CouchFunc, CouchViews, ViewCollection},
             },
         };
+        use futures_util::{stream, TryStreamExt};
         use serde_json::{json, Value};
+        use std::{collections::HashMap, time::Duration};
         use tokio::sync::{
             mpsc,
             mpsc::{Receiver, Sender},
@@ -1277,6 +1502,721 @@ mod couch_rs_tests {
             teardown(client, "should_bulk_upsert_docs").await;
         }
 
+        #[tokio::test]
+        async fn should_put_get_and_delete_an_attachment() {
+            let dbname = "should_put_get_and_delete_an_attachment";
+            let (client, db, mut doc) = setup(dbname).await;
+            let id = doc.get_id().into_owned();
+            let initial_rev = doc.get_rev().into_owned();
+
+            let details = db
+                .put_attachment(&mut doc, "hello.txt", "text/plain", "hello world")
+                .await
+                .expect("can not put attachment");
+            assert_eq!(doc.get_rev(), details.rev);
+            assert_ne!(initial_rev, details.rev);
+
+            let attachment = db
+                .get_attachment(&id, "hello.txt")
+                .await
+                .expect("can not get attachment");
+            assert_eq!(attachment.content_type, "text/plain");
+            assert_eq!(attachment.data, "hello world");
+            assert!(attachment.digest.is_some());
+
+            db.delete_attachment(&mut doc, "hello.txt")
+                .await
+                .expect("can not delete attachment");
+            assert!(db
+                .get_attachment(&id, "hello.txt")
+                .await
+                .expect_err("should be a 404")
+                .is_not_found());
+
+            teardown(client, dbname).await;
+        }
+
+        #[tokio::test]
+        async fn should_stream_an_attachment() {
+            let dbname = "should_stream_an_attachment";
+            let (client, db, mut doc) = setup(dbname).await;
+            let id = doc.get_id().into_owned();
+
+            let chunks: Vec<Result<Vec<u8>, std::io::Error>> = vec![Ok(vec![1; 1024]), Ok(vec![2; 1024])];
+            db.put_attachment_stream(&mut doc, "blob", "application/octet-stream", stream::iter(chunks))
+                .await
+                .expect("can not put attachment stream");
+
+            let attachment = db
+                .get_attachment_stream(&id, "blob")
+                .await
+                .expect("can not get attachment stream");
+            assert_eq!(attachment.content_type, "application/octet-stream");
+            let data: Vec<u8> = attachment
+                .try_fold(vec![], |mut acc, chunk| async move {
+                    acc.extend_from_slice(&chunk);
+                    Ok(acc)
+                })
+                .await
+                .expect("can not read attachment stream");
+            assert_eq!(data.len(), 2048);
+            assert_eq!(data[0], 1);
+            assert_eq!(data[2047], 2);
+
+            teardown(client, dbname).await;
+        }
+
+        #[tokio::test]
+        async fn should_save_and_get_a_document_with_attachments() {
+            let dbname = "should_save_and_get_a_document_with_attachments";
+            let (client, db, _) = setup(dbname).await;
+
+            for (id, encoding) in [
+                ("inline", AttachmentEncoding::Inline),
+                ("multipart", AttachmentEncoding::Multipart),
+            ] {
+                let mut attachments = HashMap::new();
+                attachments.insert("a.txt".to_string(), Attachment::new("text/plain", "hello"));
+                attachments.insert(
+                    "b.bin".to_string(),
+                    Attachment::new("application/octet-stream", vec![0u8, 1, 2]),
+                );
+
+                let mut doc = json!({ "_id": id, "thing": true });
+                db.create_with_attachments(&mut doc, &attachments, encoding)
+                    .await
+                    .expect("can not create document with attachments");
+
+                let result = db
+                    .get_with_attachments::<Value>(id)
+                    .await
+                    .expect("can not get document with attachments");
+                assert_eq!(result.doc["thing"], true);
+                assert_eq!(result.doc.get_rev(), doc.get_rev());
+                assert_eq!(result.attachments.len(), 2);
+                assert_eq!(result.attachments["a.txt"].data, "hello");
+                assert_eq!(result.attachments["b.bin"].data, vec![0u8, 1, 2]);
+
+                // adding an attachment keeps the existing ones
+                let mut doc = result.doc;
+                let mut extra = HashMap::new();
+                extra.insert("c.txt".to_string(), Attachment::new("text/plain", "more"));
+                db.save_with_attachments(&mut doc, &extra, encoding)
+                    .await
+                    .expect("can not save document with attachments");
+                let result = db.get_with_attachments::<Value>(id).await.unwrap();
+                assert_eq!(result.attachments.len(), 3);
+            }
+
+            teardown(client, dbname).await;
+        }
+
+        #[tokio::test]
+        async fn should_wait_for_compaction() {
+            let dbname = "should_wait_for_compaction";
+            let (client, db, _) = setup(dbname).await;
+
+            assert!(db.compact().await);
+            db.wait_for_compaction(Duration::from_millis(100), Duration::from_secs(60))
+                .await
+                .expect("can not wait for compaction");
+            let tasks = client.active_tasks().await.unwrap();
+            assert!(!tasks.iter().any(|task| task.is_for_database(dbname)));
+
+            teardown(client, dbname).await;
+        }
+
+        #[tokio::test]
+        async fn should_set_and_update_security() {
+            let dbname = "should_set_and_update_security";
+            let (client, db, _) = setup(dbname).await;
+
+            let mut security = db.get_security().await.expect("can not get security");
+            assert!(security.members.roles.is_empty());
+
+            security.admins.add_name("tenant_admin");
+            security.members.add_role("developers");
+            db.set_security(&security).await.expect("can not set security");
+
+            let security = db.add_member_role("tenant").await.expect("can not add role");
+            assert_eq!(security.members.roles, vec!["developers", "tenant"]);
+            assert_eq!(security.admins.names, vec!["tenant_admin"]);
+
+            let security = db.remove_member_role("developers").await.expect("can not remove role");
+            assert_eq!(security.members.roles, vec!["tenant"]);
+            assert_eq!(db.get_security().await.unwrap(), security);
+
+            teardown(client, dbname).await;
+        }
+
+        #[tokio::test]
+        async fn should_resolve_conflicts() {
+            let dbname = "should_resolve_conflicts";
+            let (client, db, _) = setup(dbname).await;
+
+            // create two conflicting branches, as replication would
+            let body = json!({
+                "new_edits": false,
+                "docs": [
+                    {"_id": "conflicted", "_rev": "1-aaaa", "count": 1},
+                    {"_id": "conflicted", "_rev": "1-bbbb", "count": 2},
+                    {"_id": "conflicted", "_rev": "1-cccc", "count": 3}
+                ]
+            });
+            client
+                .send(
+                    client
+                        .req(reqwest::Method::POST, &format!("{dbname}/_bulk_docs"), None)
+                        .body(body.to_string()),
+                )
+                .await
+                .expect("can not create conflicts");
+
+            let doc = db
+                .get_with_conflicts::<Value>("conflicted")
+                .await
+                .expect("can not get conflicts");
+            assert_eq!(doc.doc["_rev"], "1-cccc");
+            assert_eq!(doc.conflicts.len(), 2);
+
+            let resolved: Value = db
+                .resolve_conflicts("conflicted", &|winner: &Value, conflicts: &[Value]| {
+                    let total: i64 = conflicts
+                        .iter()
+                        .chain(Some(winner))
+                        .filter_map(|doc| doc["count"].as_i64())
+                        .sum();
+                    json!({ "count": total })
+                })
+                .await
+                .expect("can not resolve conflicts");
+            assert!(resolved["_rev"].as_str().unwrap().starts_with("2-"));
+
+            let doc = db.get_with_conflicts::<Value>("conflicted").await.unwrap();
+            assert!(!doc.has_conflicts());
+            assert_eq!(doc.doc["count"], 6);
+
+            teardown(client, dbname).await;
+        }
+
+        #[tokio::test]
+        async fn should_bulk_get() {
+            let dbname = "should_bulk_get";
+            let (client, db, doc) = setup(dbname).await;
+            let id = doc["_id"].as_str().unwrap().to_string();
+            let rev = doc["_rev"].as_str().unwrap().to_string();
+
+            let mut deleted = json!({"_id": "deleted"});
+            db.create(&mut deleted).await.expect("can not create document");
+            db.remove(&deleted).await.expect("can not remove document");
+
+            let results = db
+                .bulk_get::<Value>(
+                    vec![
+                        (s!("missing"), None),
+                        (id.clone(), Some(rev.clone())),
+                        (s!("deleted"), None),
+                        (id.clone(), None),
+                    ],
+                    true,
+                )
+                .await
+                .expect("can not bulk get documents");
+            assert_eq!(results.len(), 4);
+            assert!(matches!(&results[0], types::bulk_get::BulkGetResult::NotFound { id, .. } if id == "missing"));
+            assert!(
+                matches!(&results[1], types::bulk_get::BulkGetResult::Found(doc) if doc["_revisions"]["start"] == 1)
+            );
+            assert!(matches!(&results[2], types::bulk_get::BulkGetResult::Deleted { .. }));
+            assert_eq!(results[3].clone().found().unwrap()["_rev"], rev.as_str());
+
+            teardown(client, dbname).await;
+        }
+
+        #[tokio::test]
+        async fn should_diff_revisions() {
+            let dbname = "should_diff_revisions";
+            let (client, db, doc) = setup(dbname).await;
+            let id = doc["_id"].as_str().unwrap().to_string();
+            let rev = doc["_rev"].as_str().unwrap().to_string();
+
+            let mut revs = HashMap::new();
+            revs.insert(id.clone(), vec![rev.clone(), s!("2-7051cbe5c8faecd085a3fa619e6e6337")]);
+            revs.insert(s!("unknown"), vec![s!("1-abc")]);
+
+            let diff = db.revs_diff(&revs).await.expect("can not diff revisions");
+            assert_eq!(diff[&id].missing, vec![s!("2-7051cbe5c8faecd085a3fa619e6e6337")]);
+            assert_eq!(diff[&id].possible_ancestors, vec![rev]);
+            assert_eq!(diff["unknown"].missing, vec![s!("1-abc")]);
+
+            let missing = db.missing_revs(&revs).await.expect("can not get missing revisions");
+            assert_eq!(missing.len(), 2);
+            assert_eq!(missing[&id], vec![s!("2-7051cbe5c8faecd085a3fa619e6e6337")]);
+
+            teardown(client, dbname).await;
+        }
+
+        #[tokio::test]
+        async fn should_purge() {
+            let dbname = "should_purge";
+            let (client, db, doc) = setup(dbname).await;
+            let id = doc["_id"].as_str().unwrap().to_string();
+            let rev = doc["_rev"].as_str().unwrap().to_string();
+
+            let mut revs = HashMap::new();
+            revs.insert(id.clone(), vec![rev.clone()]);
+            let result = db.purge(revs).await.expect("can not purge document");
+            assert_eq!(result.purged[&id], vec![rev]);
+            assert!(!db.exists(&id).await);
+
+            let mut other = json!({"_id": "other"});
+            db.create(&mut other).await.expect("can not create document");
+            db.remove(&other).await.expect("can not remove document");
+            let result = db
+                .purge_all_revisions("other")
+                .await
+                .expect("can not purge all revisions");
+            assert_eq!(result.purged["other"].len(), 1);
+            assert!(db.bulk_get::<Value>(vec![(s!("other"), None)], false).await.unwrap()[0]
+                .clone()
+                .found()
+                .is_none());
+
+            db.set_purged_infos_limit(2000)
+                .await
+                .expect("can not set purged infos limit");
+            assert_eq!(db.purged_infos_limit().await.unwrap(), 2000);
+
+            teardown(client, dbname).await;
+        }
+
+        #[tokio::test]
+        async fn should_update_document() {
+            let dbname = "should_update_document";
+            let (client, db, doc) = setup(dbname).await;
+            let id = doc["_id"].as_str().unwrap().to_string();
+
+            let mut calls = 0;
+            let result = db
+                .update(&id, |doc: &mut Value| {
+                    calls += 1;
+                    doc["calls"] = json!(calls);
+                    UpdateAction::Save
+                })
+                .await
+                .expect("can not update document");
+            assert_eq!(calls, 1);
+            let saved = result.saved().unwrap();
+            assert!(saved["_rev"].as_str().unwrap().starts_with("2-"));
+
+            // the first attempt writes an outdated revision, as when the document changed concurrently
+            let first_rev = doc["_rev"].as_str().unwrap().to_string();
+            let mut calls = 0;
+            let result = db
+                .update(&id, |doc: &mut Value| {
+                    calls += 1;
+                    if calls == 1 {
+                        doc["_rev"] = json!(first_rev);
+                    }
+                    doc["updated"] = json!(true);
+                    UpdateAction::Save
+                })
+                .await
+                .expect("can not update document");
+            assert_eq!(calls, 2);
+            let saved = result.saved().unwrap();
+            assert_eq!(saved["calls"], 1);
+            assert_eq!(saved["updated"], true);
+
+            let err = db
+                .update_with_attempts(&id, 1, |doc: &mut Value| {
+                    doc["_rev"] = json!(first_rev);
+                    UpdateAction::Save
+                })
+                .await
+                .unwrap_err();
+            assert_eq!(err.status(), Some(reqwest::StatusCode::CONFLICT));
+
+            let result = db.update(&id, |_: &mut Value| UpdateAction::Abort).await.unwrap();
+            assert!(matches!(result, UpdateResult::Aborted(doc) if doc["updated"] == true));
+
+            let result = db.update(&id, |_: &mut Value| UpdateAction::Delete).await.unwrap();
+            assert!(matches!(result, UpdateResult::Deleted(rev) if rev.starts_with("4-")));
+            assert!(!db.exists(&id).await);
+
+            teardown(client, dbname).await;
+        }
+
+        #[tokio::test]
+        async fn should_patch_documents() {
+            let dbname = "should_patch_documents";
+            let (client, db, doc) = setup(dbname).await;
+            let id = doc["_id"].as_str().unwrap().to_string();
+
+            let patch = Patch::Json(vec![
+                PatchOperation::Test {
+                    path: s!("/thing"),
+                    value: json!(true),
+                },
+                PatchOperation::Add {
+                    path: s!("/tags"),
+                    value: json!(["a"]),
+                },
+            ]);
+            let patched: Value = db.patch(&id, &patch).await.expect("can not patch document");
+            assert_eq!(patched["tags"], json!(["a"]));
+            assert!(patched["_rev"].as_str().unwrap().starts_with("2-"));
+
+            let failing = Patch::Json(vec![PatchOperation::Test {
+                path: s!("/thing"),
+                value: json!(false),
+            }]);
+            let err = db.patch::<Value>(&id, &failing).await.unwrap_err();
+            assert!(matches!(err, CouchError::PatchTestFailed(_)));
+
+            let mut other = json!({"_id": "other", "thing": false});
+            db.create(&mut other).await.unwrap();
+            let results = db
+                .bulk_patch(vec![
+                    (id.clone(), Patch::Merge(json!({"tags": null}))),
+                    (s!("other"), patch.clone()),
+                    (s!("missing"), Patch::Merge(json!({}))),
+                ])
+                .await
+                .expect("can not bulk patch documents");
+            assert!(results[0].as_ref().unwrap().rev.starts_with("3-"));
+            assert!(matches!(&results[1], Err(CouchError::PatchTestFailed(_))));
+            assert!(results[2].as_ref().unwrap_err().is_not_found());
+
+            let stored: Value = db.get(&id).await.unwrap();
+            assert!(stored.get("tags").is_none());
+
+            teardown(client, dbname).await;
+        }
+
+        #[tokio::test]
+        async fn should_write_with_options() {
+            let dbname = "should_write_with_options";
+            let (client, db, _) = setup(dbname).await;
+
+            let mut reading = json!({"_id": "reading", "celsius": 21.5});
+            let rev = db.save(&mut reading).await.unwrap().rev;
+            reading["celsius"] = json!(21.7);
+            let written = db
+                .save_with_options(&mut reading, &WriteOptions::default().batch(true))
+                .await
+                .expect("can not save in batch mode");
+            assert_eq!(written, DocumentWritten::Accepted { id: s!("reading") });
+            assert_eq!(reading["_rev"], rev.as_str());
+
+            let mut reading = json!({"celsius": 22.0});
+            let written = db
+                .create_with_options(&mut reading, &WriteOptions::default().batch(true))
+                .await
+                .expect("can not create in batch mode");
+            assert_eq!(written.rev(), None);
+            assert_eq!(reading["_id"], written.id());
+
+            let rev = s!("3-917fa2381192822767f010b95b45325b");
+            let mut docs = vec![json!({"_id": "imported", "_rev": rev, "v": 1})];
+            let results = db
+                .bulk_docs_with_options(&mut docs, &WriteOptions::default().new_edits(false))
+                .await
+                .expect("can not import documents");
+            assert_eq!(results[0].as_ref().unwrap().rev, rev);
+            assert_eq!(docs[0]["_rev"], rev.as_str());
+            let imported: Value = db.get("imported").await.unwrap();
+            assert_eq!(imported["_rev"], rev.as_str());
+
+            teardown(client, dbname).await;
+        }
+
+        #[tokio::test]
+        async fn should_remove_documents() {
+            let dbname = "should_remove_documents";
+            let (client, db, doc) = setup(dbname).await;
+
+            let mut outdated = doc.clone();
+            outdated["_rev"] = json!("1-00000000000000000000000000000000");
+            let err = db.remove(&outdated).await.unwrap_err();
+            assert_eq!(err.status(), Some(reqwest::StatusCode::CONFLICT));
+
+            let rev = db.remove(&doc).await.expect("can not remove document");
+            assert!(rev.starts_with("2-"));
+            let err = db.remove(&doc).await.unwrap_err();
+            assert_eq!(err.status(), Some(reqwest::StatusCode::CONFLICT));
+            assert!(db.remove_by_id("missing", "1-abc").await.unwrap_err().is_not_found());
+
+            let mut docs = vec![json!({"_id": "a"}), json!({"_id": "b"})];
+            db.bulk_docs(&mut docs).await.unwrap();
+            let rev = db
+                .remove_by_id("a", docs[0]["_rev"].as_str().unwrap())
+                .await
+                .expect("can not remove document by id");
+            assert!(rev.starts_with("2-"));
+
+            let results = db.bulk_remove(&docs).await.expect("can not remove documents");
+            assert_eq!(
+                results[0].as_ref().unwrap_err().status(),
+                Some(reqwest::StatusCode::CONFLICT)
+            );
+            assert!(results[1].as_ref().unwrap().rev.starts_with("2-"));
+            assert!(!db.exists("b").await);
+
+            teardown(client, dbname).await;
+        }
+
+        #[tokio::test]
+        async fn should_query_partition() {
+            let db_name = "should_query_partition";
+            let client = Client::new_local_test().unwrap();
+            let db = client
+                .make_db_with_options(db_name, &CreateDbOptions::default().partitioned(true))
+                .await
+                .unwrap();
+            assert_eq!(client.get_info(db_name).await.unwrap().props.partitioned, Some(true));
+
+            let partition = db.partition("sensor-1").unwrap();
+            for (id, temperature) in [("sensor-1:a", 18), ("sensor-1:b", 22), ("sensor-2:a", 25)] {
+                db.create(&mut json!({"_id": id, "temperature": temperature}))
+                    .await
+                    .unwrap();
+            }
+            db.create_view(
+                "by_temperature",
+                CouchViews::new(
+                    "by_temperature",
+                    CouchFunc {
+                        map: "function(doc) { emit(doc.temperature, null); }".to_string(),
+                        reduce: None,
+                    },
+                ),
+            )
+            .await
+            .unwrap();
+
+            let all: DocumentCollection<Value> = partition.all_docs(None).await.unwrap();
+            assert_eq!(all.total_rows, 2);
+            let err = partition
+                .all_docs::<Value>(Some(QueryParams::from_keys(vec!["sensor-2:a".to_string()])))
+                .await
+                .unwrap_err();
+            assert_eq!(err.status(), Some(reqwest::StatusCode::BAD_REQUEST));
+
+            let query = FindQuery::new(json!({"temperature": {"$gt": 20}}));
+            let warm: DocumentCollection<Value> = partition.find(&query).await.unwrap();
+            assert_eq!(warm.rows.len(), 1);
+            assert_eq!(warm.rows[0]["_id"], "sensor-1:b");
+            assert!(partition.explain(&query).await.unwrap().get("index").is_some());
+
+            let view: ViewCollection<u32, Value, Value> =
+                partition.query("by_temperature", "by_temperature", None).await.unwrap();
+            assert_eq!(view.rows.iter().map(|row| row.key).collect::<Vec<_>>(), vec![18, 22]);
+
+            let info = partition.info().await.unwrap();
+            assert_eq!(info.partition, "sensor-1");
+            assert_eq!(info.doc_count, 2);
+            teardown(client, db_name).await;
+        }
+
+        #[tokio::test]
+        async fn should_manage_design_documents() {
+            let db_name = "should_manage_design_documents";
+            let (client, db, _doc) = setup(db_name).await;
+
+            let mut design = DesignDocument::new("reports");
+            design.views.insert(
+                "things".to_string(),
+                CouchFunc::new("function (doc) { if (doc.thing) { emit(doc._id, null); } }", None).into(),
+            );
+            design.validate_doc_update =
+                Some("function (doc) { if (doc.forbidden) { throw({forbidden: 'not allowed'}); } }".to_string());
+            design.options = Some(DesignOptions {
+                local_seq: Some(true),
+                ..DesignOptions::default()
+            });
+            db.put_design(&mut design).await.unwrap();
+            assert!(design.rev.starts_with("1-"));
+
+            // a second deploy uses the revision of the first
+            design.autoupdate = Some(false);
+            db.put_design(&mut design).await.unwrap();
+            let stored = db.get_design("reports").await.unwrap();
+            assert_eq!(stored, design);
+
+            let err = db.create(&mut json!({"forbidden": true})).await.unwrap_err();
+            assert_eq!(err.status(), Some(reqwest::StatusCode::FORBIDDEN));
+
+            // the design document of a Mango index is listed as well
+            let spec = types::index::IndexFields::new(vec![types::find::SortSpec::Simple(s!("thing"))]);
+            db.insert_index("thing-index", spec, None, Some(s!("thing-index")))
+                .await
+                .unwrap();
+            let listed: ViewCollection<String, Value, DesignDocument> = db
+                .list_design_docs(Some(QueryParams::default().include_docs(true)))
+                .await
+                .unwrap();
+            let names: Vec<_> = listed.rows.iter().map(|row| row.doc.as_ref().unwrap().name()).collect();
+            assert_eq!(names, vec!["reports", "thing-index"]);
+
+            db.delete_design("reports", &design.rev).await.unwrap();
+            assert!(db.get_design("reports").await.unwrap_err().is_not_found());
+            teardown(client, db_name).await;
+        }
+
+        #[tokio::test]
+        async fn should_replace_existing_view() {
+            let db_name = "should_replace_existing_view";
+            let (client, db, _doc) = setup(db_name).await;
+            for map in [
+                "function (doc) { emit(doc._id, 1); }",
+                "function (doc) { emit(doc._id, 2); }",
+            ] {
+                db.create_view("replaced", CouchViews::new("values", CouchFunc::new(map, None)))
+                    .await
+                    .unwrap();
+            }
+            let result: ViewCollection<String, u32, Value> = db.query("replaced", "values", None).await.unwrap();
+            assert_eq!(result.rows[0].value, 2);
+            teardown(client, db_name).await;
+        }
+
+        #[tokio::test]
+        async fn should_copy_document() {
+            let dbname = "should_copy_document";
+            let (client, db, doc) = setup(dbname).await;
+            let id = doc["_id"].as_str().unwrap().to_string();
+
+            let details = db.copy(&id, None, "copy 1", None).await.expect("can not copy document");
+            assert_eq!(details.id, "copy 1");
+            let copy: Value = db.get("copy 1").await.unwrap();
+            assert_eq!(copy["thing"], doc["thing"]);
+
+            let err = db.copy(&id, None, "copy 1", None).await.unwrap_err();
+            assert_eq!(err.status(), Some(reqwest::StatusCode::CONFLICT));
+            db.copy(&id, None, "copy 1", Some(&details.rev))
+                .await
+                .expect("can not overwrite copy");
+
+            let err = db.copy("missing", None, "copy 2", None).await.unwrap_err();
+            assert!(err.is_not_found());
+
+            teardown(client, dbname).await;
+        }
+
+        #[tokio::test]
+        async fn should_manage_local_documents() {
+            let dbname = "should_manage_local_documents";
+            let (client, db, _) = setup(dbname).await;
+
+            let mut checkpoint = json!({"_id": "node 1/checkpoint", "seq": 1});
+            let details = db
+                .save_local(&mut checkpoint)
+                .await
+                .expect("can not save local document");
+            assert_eq!(details.id, "_local/node 1/checkpoint");
+            assert_eq!(details.rev, "0-1");
+
+            checkpoint["seq"] = json!(2);
+            db.save_local(&mut checkpoint)
+                .await
+                .expect("can not update local document");
+            assert_eq!(checkpoint["_rev"], "0-2");
+
+            let stored: Value = db
+                .get_local("node 1/checkpoint")
+                .await
+                .expect("can not get local document");
+            assert_eq!(stored["seq"], 2);
+            let stored: Value = db.get("_local/node 1/checkpoint").await.unwrap();
+            assert_eq!(stored["_rev"], "0-2");
+
+            let local_docs = db
+                .list_local_docs::<Value>(Some(QueryParams::default().include_docs(true)))
+                .await
+                .expect("can not list local documents");
+            assert_eq!(local_docs.rows.len(), 1);
+            assert_eq!(local_docs.rows[0].key, "_local/node 1/checkpoint");
+            assert_eq!(local_docs.rows[0].doc.as_ref().unwrap()["seq"], 2);
+            assert_eq!(db.get_all_raw().await.unwrap().rows.len(), 1);
+
+            db.remove_local(&stored).await.expect("can not remove local document");
+            assert!(db
+                .get_local::<Value>("node 1/checkpoint")
+                .await
+                .unwrap_err()
+                .is_not_found());
+
+            teardown(client, dbname).await;
+        }
+
+        #[tokio::test]
+        async fn should_get_with_options() {
+            let dbname = "should_get_with_options";
+            let (client, db, mut doc) = setup(dbname).await;
+            let first_rev = doc["_rev"].as_str().unwrap().to_string();
+            let id = doc["_id"].as_str().unwrap().to_string();
+
+            doc["thing"] = json!("updated");
+            db.save(&mut doc).await.expect("can not update document");
+
+            let options = GetOptions::default().local_seq(true).revs_info(true);
+            let result = db
+                .get_with_options::<Value>(&id, &options)
+                .await
+                .expect("can not get document with options");
+            assert!(result.local_seq.is_some());
+            assert_eq!(result.revs_info.len(), 2);
+            assert!(result.conflicts.is_empty());
+            assert!(result.doc.get("_local_seq").is_none());
+            assert_eq!(result.doc["thing"], "updated");
+
+            let options = GetOptions::default().rev(&first_rev).latest(true);
+            let latest = db
+                .get_with_options::<Value>(&id, &options)
+                .await
+                .expect("can not get latest revision");
+            assert_eq!(latest.doc["_rev"], doc["_rev"]);
+
+            teardown(client, dbname).await;
+        }
+
+        #[tokio::test]
+        async fn should_get_revisions() {
+            let dbname = "should_get_revisions";
+            let (client, db, mut doc) = setup(dbname).await;
+            let first_rev = doc["_rev"].as_str().unwrap().to_string();
+
+            doc["thing"] = json!("updated");
+            db.save(&mut doc).await.expect("can not update document");
+            let id = doc["_id"].as_str().unwrap().to_string();
+
+            let old: Value = db.get_rev(&id, &first_rev).await.expect("can not get revision");
+            assert_eq!(old["_rev"], first_rev.as_str());
+            assert_ne!(old["thing"], "updated");
+
+            let history = db.get_revisions::<Value>(&id).await.expect("can not get revisions");
+            assert_eq!(history.revisions.len(), 2);
+            assert_eq!(history.revisions[1], first_rev);
+            assert_eq!(history.revs_info[0].status, RevisionStatus::Available);
+            assert!(history.doc.get("_revisions").is_none());
+
+            let open = db
+                .get_open_revs::<Value>(&id, OpenRevs::Specific(vec![first_rev.clone(), s!("9-missing")]))
+                .await
+                .expect("can not get open revs");
+            assert!(matches!(&open[0], OpenRevision::Found(doc) if doc["_rev"] == first_rev.as_str()));
+            assert_eq!(open[1], OpenRevision::Missing(s!("9-missing")));
+
+            let all = db
+                .get_open_revs::<Value>(&id, OpenRevs::All)
+                .await
+                .expect("can not get open revs");
+            assert_eq!(all.len(), 1);
+
+            teardown(client, dbname).await;
+        }
+
         #[tokio::test]
         async fn should_retrieve_membership() {
             let client = Client::new_local_test().unwrap();
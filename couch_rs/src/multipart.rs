@@ -0,0 +1,203 @@
+use crate::error::{CouchError, CouchResult};
+use bytes::{Bytes, BytesMut};
+use reqwest::StatusCode;
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, Hasher},
+};
+
+/// A single part of a multipart body.
+#[derive(Debug, Clone)]
+pub(crate) struct Part {
+    /// Part headers, with lower-cased names
+    pub headers: HashMap<String, String>,
+    pub body: Bytes,
+}
+
+impl Part {
+    pub fn new(content_type: &str, body: Bytes) -> Self {
+        let mut headers = HashMap::new();
+        headers.insert("content-type".to_string(), content_type.to_string());
+        Part { headers, body }
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(String::as_str)
+    }
+
+    pub fn content_type(&self) -> Option<&str> {
+        self.header("content-type")
+    }
+
+    /// The `filename` of the `Content-Disposition` header, used by `CouchDB` to name attachment parts.
+    pub fn filename(&self) -> Option<String> {
+        self.header("content-disposition").and_then(|disposition| {
+            disposition.split(';').find_map(|param| {
+                let (key, value) = param.trim().split_once('=')?;
+                (key.trim() == "filename").then(|| value.trim().trim_matches('"').to_string())
+            })
+        })
+    }
+}
+
+/// Generates a boundary that is very unlikely to show up in any of the parts.
+pub(crate) fn new_boundary() -> String {
+    let random = std::collections::hash_map::RandomState::new().build_hasher().finish();
+    format!("couch_rs_{random:016x}")
+}
+
+/// Extracts the boundary parameter from a multipart `Content-Type` header.
+pub(crate) fn boundary(content_type: &str) -> Option<String> {
+    content_type.split(';').find_map(|param| {
+        let (key, value) = param.trim().split_once('=')?;
+        key.trim()
+            .eq_ignore_ascii_case("boundary")
+            .then(|| value.trim().trim_matches('"').to_string())
+    })
+}
+
+/// Builds a multipart body from the given parts.
+pub(crate) fn build(boundary: &str, parts: &[Part]) -> Bytes {
+    let mut body = BytesMut::new();
+    for part in parts {
+        body.extend_from_slice(format!("--{boundary}\r\n").as_bytes());
+        for (name, value) in &part.headers {
+            body.extend_from_slice(format!("{name}: {value}\r\n").as_bytes());
+        }
+        body.extend_from_slice(b"\r\n");
+        body.extend_from_slice(&part.body);
+        body.extend_from_slice(b"\r\n");
+    }
+    body.extend_from_slice(format!("--{boundary}--").as_bytes());
+    body.freeze()
+}
+
+/// Splits a multipart body into its parts.
+pub(crate) fn parse(body: &Bytes, boundary: &str) -> CouchResult<Vec<Part>> {
+    let delimiter = format!("--{boundary}");
+    let delimiter = delimiter.as_bytes();
+    let mut parts = vec![];
+
+    let Some(mut pos) = find(body, delimiter, 0) else {
+        return Err(invalid("no boundary found"));
+    };
+
+    loop {
+        pos += delimiter.len();
+        if body[pos..].starts_with(b"--") {
+            // closing delimiter
+            break;
+        }
+
+        // skip the remainder of the delimiter line
+        let start = find(body, b"\r\n", pos).ok_or_else(|| invalid("unterminated boundary"))? + 2;
+        let end = find(body, delimiter, start).ok_or_else(|| invalid("missing closing boundary"))?;
+        // the CRLF preceding the delimiter belongs to the delimiter
+        let content_end = if end >= 2 && &body[end - 2..end] == b"\r\n" {
+            end - 2
+        } else {
+            end
+        };
+
+        parts.push(parse_part(&body.slice(start..content_end.max(start)))?);
+        pos = end;
+    }
+
+    Ok(parts)
+}
+
+fn parse_part(part: &Bytes) -> CouchResult<Part> {
+    let (header_block, body) = if part.starts_with(b"\r\n") {
+        (&part[0..0], part.slice(2..))
+    } else {
+        let split = find(part, b"\r\n\r\n", 0).ok_or_else(|| invalid("missing part headers"))?;
+        (&part[..split], part.slice(split + 4..))
+    };
+
+    let headers = String::from_utf8_lossy(header_block)
+        .split("\r\n")
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            Some((name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        })
+        .collect();
+
+    Ok(Part { headers, body })
+}
+
+fn find(haystack: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    if from > haystack.len() {
+        return None;
+    }
+    haystack[from..]
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|p| p + from)
+}
+
+fn invalid(reason: &str) -> CouchError {
+    CouchError::new(
+        format!("invalid multipart response: {reason}"),
+        StatusCode::INTERNAL_SERVER_ERROR,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_extract_boundary() {
+        assert_eq!(
+            boundary(r#"multipart/related; boundary="abc123""#),
+            Some("abc123".to_string())
+        );
+        assert_eq!(boundary("multipart/mixed;boundary=xyz"), Some("xyz".to_string()));
+        assert_eq!(boundary("application/json"), None);
+    }
+
+    #[test]
+    fn can_build_and_parse_parts() {
+        let boundary = new_boundary();
+        let parts = vec![
+            Part::new("application/json", Bytes::from_static(br#"{"_id":"1"}"#)),
+            Part::new("application/octet-stream", Bytes::from_static(b"\r\nbinary\r\n--data")),
+        ];
+
+        let body = build(&boundary, &parts);
+        let parsed = parse(&body, &boundary).expect("should parse");
+        assert_eq!(parsed.len(), 2);
+        assert_eq!(parsed[0].content_type(), Some("application/json"));
+        assert_eq!(parsed[0].body, r#"{"_id":"1"}"#);
+        assert_eq!(parsed[1].body, "\r\nbinary\r\n--data");
+    }
+
+    #[test]
+    fn can_parse_couchdb_response() {
+        let body = Bytes::from_static(
+            b"--e89b3e29388aef23453450d10e5aaed0\r\n\
+Content-Type: application/json\r\n\
+\r\n\
+{\"_id\":\"secret\",\"_rev\":\"2-c1c6c44c4bc3c9344b037c8690468605\",\"_attachments\":{\"recipe.txt\":{\"content_type\":\"text/plain\",\"revpos\":2,\"digest\":\"md5-HV9aXJdEnu0xnMQYTKgOFA==\",\"length\":86,\"follows\":true}}}\r\n\
+--e89b3e29388aef23453450d10e5aaed0\r\n\
+Content-Disposition: attachment; filename=\"recipe.txt\"\r\n\
+Content-Type: text/plain\r\n\
+Content-Length: 5\r\n\
+\r\n\
+hello\r\n\
+--e89b3e29388aef23453450d10e5aaed0--",
+        );
+
+        let parts = parse(&body, "e89b3e29388aef23453450d10e5aaed0").expect("should parse");
+        assert_eq!(parts.len(), 2);
+        assert!(parts[0].body.starts_with(b"{\"_id\":\"secret\""));
+        assert_eq!(parts[1].filename(), Some("recipe.txt".to_string()));
+        assert_eq!(parts[1].body, "hello");
+    }
+
+    #[test]
+    fn rejects_missing_boundary() {
+        let body = Bytes::from_static(b"not multipart");
+        assert!(parse(&body, "abc").is_err());
+    }
+}
@@ -0,0 +1,183 @@
+use crate::{
+    database::Database,
+    document::{DocumentCollection, TypedCouchDocument},
+    error::{CouchError, CouchResult},
+    types::{document::DocumentId, find::FindQuery, query::QueryParams, system::PartitionInfo, view::ViewCollection},
+};
+use reqwest::StatusCode;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::fmt::Debug;
+
+/// Queries on a partition of a partitioned database, see
+/// [partitioned databases](https://docs.couchdb.org/en/stable/partitioned-dbs/index.html).
+/// The documents of a partition have ids like `partition:docid`; create one through
+/// [`Database::partition`](crate::database::Database::partition).
+#[derive(Debug, Clone)]
+pub struct Partition {
+    db: Database,
+    name: String,
+}
+
+impl Partition {
+    pub(crate) fn new(db: Database, name: &str) -> CouchResult<Partition> {
+        validate_partition_name(name)?;
+        Ok(Partition {
+            db,
+            name: name.to_string(),
+        })
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The id of a document in this partition, like `partition:docid`.
+    pub fn doc_id(&self, id: &str) -> CouchResult<DocumentId> {
+        if id.is_empty() {
+            return Err(bad_request("a document id can not be empty"));
+        }
+        Ok(format!("{}:{}", self.name, id))
+    }
+
+    fn create_path(&self, path: &str) -> String {
+        let name = &self.name;
+        format!("{}/_partition/{}/{}", self.db.name(), url_encode!(name), path)
+    }
+
+    /// Checks that a document id belongs to this partition.
+    fn validate_doc_id(&self, id: &str) -> CouchResult<()> {
+        match id.split_once(':') {
+            Some((partition, _)) if partition == self.name => Ok(()),
+            _ => Err(bad_request(&format!(
+                "document id {id} is not in partition {}",
+                self.name
+            ))),
+        }
+    }
+
+    /// Gets the documents of the partition through `_all_docs`, see
+    /// [`Database::get_all_params`](crate::database::Database::get_all_params). The keys must be ids of this
+    /// partition.
+    pub async fn all_docs<T: TypedCouchDocument>(
+        &self,
+        params: Option<QueryParams<DocumentId>>,
+    ) -> CouchResult<DocumentCollection<T>> {
+        if let Some(params) = &params {
+            let ids = params
+                .key
+                .iter()
+                .chain(&params.keys)
+                .chain(&params.start_key)
+                .chain(&params.end_key)
+                .chain(&params.start_key_doc_id)
+                .chain(&params.end_key_doc_id);
+            for id in ids {
+                self.validate_doc_id(id)?;
+            }
+        }
+        self.db.all_docs_at(&self.create_path("_all_docs"), params).await
+    }
+
+    /// Finds documents of the partition through a Mango query, see
+    /// [`Database::find`](crate::database::Database::find).
+    pub async fn find<T: TypedCouchDocument>(&self, query: &FindQuery) -> CouchResult<DocumentCollection<T>> {
+        self.db.find_at(&self.create_path("_find"), query).await
+    }
+
+    /// Executes a query against a view of a partitioned design document, see
+    /// [`Database::query`](crate::database::Database::query).
+    pub async fn query<
+        K: Serialize + DeserializeOwned + PartialEq + Debug + Clone,
+        V: DeserializeOwned,
+        T: TypedCouchDocument,
+    >(
+        &self,
+        design_name: &str,
+        view_name: &str,
+        options: Option<QueryParams<K>>,
+    ) -> CouchResult<ViewCollection<K, V, T>> {
+        if let Some(options) = &options {
+            for id in options.start_key_doc_id.iter().chain(&options.end_key_doc_id) {
+                self.validate_doc_id(id)?;
+            }
+        }
+        let path = self.create_path(&format!(
+            "_design/{}/_view/{}",
+            url_encode!(design_name),
+            url_encode!(view_name)
+        ));
+        self.db.query_at(&path, options).await
+    }
+
+    /// Shows which index a Mango query would use, without running it.
+    pub async fn explain(&self, query: &FindQuery) -> CouchResult<Value> {
+        let client = self.db.client();
+        client
+            .send(client.post(&self.create_path("_explain"), js!(query)))
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .map_err(CouchError::from)
+    }
+
+    /// Gets the document count and size of the partition.
+    pub async fn info(&self) -> CouchResult<PartitionInfo> {
+        let client = self.db.client();
+        let name = &self.name;
+        let path = format!("{}/_partition/{}", self.db.name(), url_encode!(name));
+        client
+            .send(client.get(&path, None))
+            .await?
+            .error_for_status()?
+            .json()
+            .await
+            .map_err(CouchError::from)
+    }
+}
+
+/// Partition names can not be empty, start with an underscore or contain a colon.
+fn validate_partition_name(name: &str) -> CouchResult<()> {
+    if name.is_empty() || name.starts_with('_') || name.contains(':') {
+        Err(bad_request(&format!("invalid partition name: {name:?}")))
+    } else {
+        Ok(())
+    }
+}
+
+fn bad_request(message: &str) -> CouchError {
+    CouchError::new(message.to_string(), StatusCode::BAD_REQUEST)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Client;
+
+    fn partition(name: &str) -> CouchResult<Partition> {
+        let client = Client::new_local_test().unwrap();
+        Database::new("sensors".to_string(), client).partition(name)
+    }
+
+    #[test]
+    fn validates_partition_names() {
+        for name in ["", "_design", "a:b"] {
+            let err = partition(name).unwrap_err();
+            assert_eq!(err.status(), Some(StatusCode::BAD_REQUEST));
+        }
+        assert_eq!(partition("sensor-1").unwrap().name(), "sensor-1");
+    }
+
+    #[test]
+    fn can_build_partition_paths_and_ids() {
+        let partition = partition("sensor-1").unwrap();
+        assert_eq!(partition.create_path("_find"), "sensors/_partition/sensor-1/_find");
+        assert_eq!(partition.doc_id("reading").unwrap(), "sensor-1:reading");
+        assert!(partition.doc_id("").is_err());
+        assert!(partition.validate_doc_id("sensor-1:reading").is_ok());
+        assert!(partition.validate_doc_id("sensor-2:reading").is_err());
+        assert!(partition.validate_doc_id("reading").is_err());
+    }
+}
@@ -0,0 +1,357 @@
+use crate::error::{CouchError, CouchResult};
+use reqwest::{header::RETRY_AFTER, Method, Response, StatusCode, Url};
+use std::{
+    collections::hash_map::RandomState,
+    error::Error,
+    fmt,
+    hash::{BuildHasher, Hasher},
+    sync::Arc,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Paths that are requested with a POST, but only read from the database; these are always safe to retry.
+const READ_ONLY_POSTS: &[&str] = &[
+    "_all_docs",
+    "_find",
+    "_explain",
+    "_bulk_get",
+    "_revs_diff",
+    "_missing_revs",
+    "_local_docs",
+    "_design_docs",
+];
+
+/// Observes retries; see [`RetryPolicy::on_retry`].
+pub type RetryHook = Arc<dyn Fn(&RetryEvent<'_>) + Send + Sync>;
+
+/// How the [`Client`](crate::Client) retries requests that failed due to a transient error.
+///
+/// Requests that only read data, like GET and HEAD requests, `_all_docs`, `_find` and view queries, are retried on
+/// connection errors, timeouts and the configured status codes (by default 429, 502, 503 and 504). All other requests,
+/// like `save` and `bulk_docs`, are only retried when the connection could not be established, because the request
+/// was not sent yet in that case. Requests with a streaming body are never retried.
+///
+/// The delay between attempts grows exponentially, with jitter, up to the maximum backoff. When `CouchDB` (or a proxy)
+/// sends a `Retry-After` header, that delay is used instead. When it is longer than the maximum backoff, the request is
+/// not retried, and the response is returned as is.
+///
+/// Usage:
+/// ```
+/// use couch_rs::{error::CouchResult, retry::RetryPolicy, Client};
+/// use std::time::Duration;
+///
+/// #[tokio::main]
+/// async fn main() -> CouchResult<()> {
+///     let retry = RetryPolicy::new(5)
+///         .backoff(Duration::from_millis(200), Duration::from_secs(5))
+///         .on_retry(|event| println!("retrying {} {}: {:?}", event.method, event.url, event.reason));
+///     let client = Client::builder("http://localhost:5984").retry(retry).build()?;
+///     let dbs = client.list_dbs().await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Clone)]
+#[must_use]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    jitter: bool,
+    statuses: Vec<StatusCode>,
+    on_retry: Option<RetryHook>,
+}
+
+/// Why a request is retried.
+#[derive(Debug, Clone)]
+pub enum RetryReason {
+    /// The server responded with a retryable status code
+    Status(StatusCode),
+    /// The request failed before a response was received
+    Error(CouchError),
+}
+
+/// Passed to the [`RetryPolicy::on_retry`] hook, right before waiting for the next attempt.
+#[derive(Debug)]
+pub struct RetryEvent<'a> {
+    pub method: &'a Method,
+    pub url: &'a Url,
+    /// The attempt that failed, starting at 1
+    pub attempt: u32,
+    /// How long to wait before the next attempt
+    pub delay: Duration,
+    pub reason: RetryReason,
+}
+
+impl RetryPolicy {
+    /// Creates a policy that sends a request at most `max_attempts` times, including the first attempt.
+    pub fn new(max_attempts: u32) -> Self {
+        RetryPolicy {
+            max_attempts: max_attempts.max(1),
+            ..RetryPolicy::default()
+        }
+    }
+
+    /// Sets the delay before the first retry, which is doubled for each following retry up to `max`.
+    /// Defaults to 100 milliseconds and 10 seconds.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max.max(initial);
+        self
+    }
+
+    /// Enables or disables the randomization of the delays, which spreads the retries of concurrent requests.
+    /// Enabled by default.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the status codes that are retried. Defaults to 429, 502, 503 and 504.
+    pub fn statuses(mut self, statuses: &[StatusCode]) -> Self {
+        self.statuses = statuses.to_vec();
+        self
+    }
+
+    /// Sets a hook that is called before each retry, e.g. for logging or metrics.
+    pub fn on_retry(mut self, hook: impl Fn(&RetryEvent<'_>) + Send + Sync + 'static) -> Self {
+        self.on_retry = Some(Arc::new(hook));
+        self
+    }
+
+    #[must_use]
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Decides whether the outcome of an attempt should be retried, and if so, how long to wait first.
+    pub(crate) fn retry_after(
+        &self,
+        method: &Method,
+        url: &Url,
+        attempt: u32,
+        outcome: &CouchResult<Response>,
+    ) -> Option<(Duration, RetryReason)> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+
+        let idempotent = is_idempotent(method, url);
+        let (reason, retry_after) = match outcome {
+            Ok(response) if idempotent && self.statuses.contains(&response.status()) => {
+                (RetryReason::Status(response.status()), retry_after_header(response))
+            }
+            Err(err) if is_transient(err, idempotent) => (RetryReason::Error(err.clone()), None),
+            _ => return None,
+        };
+
+        let delay = match retry_after {
+            // retrying before the server allows it would only be rejected again
+            Some(delay) if delay > self.max_backoff => return None,
+            Some(delay) => delay,
+            None => self.delay(attempt),
+        };
+        Some((delay, reason))
+    }
+
+    pub(crate) fn notify(&self, event: &RetryEvent<'_>) {
+        if let Some(hook) = &self.on_retry {
+            hook(event);
+        }
+    }
+
+    /// The exponential backoff for the given attempt, with "equal jitter": half of the delay is fixed, the other half
+    /// is random.
+    fn delay(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(31);
+        let delay = self.initial_backoff.saturating_mul(1 << exponent).min(self.max_backoff);
+        if !self.jitter {
+            return delay;
+        }
+
+        let half = delay / 2;
+        let random = RandomState::new().build_hasher().finish();
+        half + half.mul_f64(random as f64 / u64::MAX as f64)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            statuses: vec![
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::BAD_GATEWAY,
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+            ],
+            on_retry: None,
+        }
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_attempts", &self.max_attempts)
+            .field("initial_backoff", &self.initial_backoff)
+            .field("max_backoff", &self.max_backoff)
+            .field("jitter", &self.jitter)
+            .field("statuses", &self.statuses)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Whether a request only reads data, and can therefore safely be sent more than once.
+pub(crate) fn is_idempotent(method: &Method, url: &Url) -> bool {
+    match *method {
+        Method::GET | Method::HEAD => true,
+        Method::POST => url.path_segments().is_some_and(|segments| {
+            let segments: Vec<&str> = segments.collect();
+            match segments.as_slice() {
+                // multiple queries, on `_all_docs`, `_design_docs`, `_local_docs` or a view
+                [.., "_all_docs" | "_design_docs" | "_local_docs", "queries"]
+                | [.., "_design", _, "_view", _, "queries"] => true,
+                [.., "_design", _, "_view", _] => true,
+                [.., last] => READ_ONLY_POSTS.contains(last),
+                [] => false,
+            }
+        }),
+        _ => false,
+    }
+}
+
+/// Whether an error is worth retrying; only connection errors are safe for requests that modify data.
+fn is_transient(err: &CouchError, idempotent: bool) -> bool {
+    let Some(err) = err.source().and_then(|source| source.downcast_ref::<reqwest::Error>()) else {
+        return false;
+    };
+    err.is_connect() || (idempotent && (err.is_timeout() || err.is_request()))
+}
+
+fn retry_after_header(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    parse_http_date(value)?.duration_since(SystemTime::now()).ok()
+}
+
+/// Parses an IMF-fixdate, like `Sun, 06 Nov 1994 08:49:37 GMT`; the preferred format of HTTP dates.
+fn parse_http_date(value: &str) -> Option<SystemTime> {
+    let (_, date) = value.split_once(", ")?;
+    let mut parts = date.split(' ');
+    let day: u64 = parts.next()?.parse().ok()?;
+    let month = parts.next()?;
+    let year: i64 = parts.next()?.parse().ok()?;
+    let time = parts.next()?;
+    if parts.next()? != "GMT" {
+        return None;
+    }
+
+    let month = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ]
+    .iter()
+    .position(|m| *m == month)? as i64
+        + 1;
+    let mut time = time.split(':').map(|t| t.parse::<u64>().ok());
+    let (hours, minutes, seconds) = (time.next()??, time.next()??, time.next()??);
+
+    // days since the epoch, see http://howardhinnant.github.io/date_algorithms.html#days_from_civil
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * (month + if month > 2 { -3 } else { 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = u64::try_from(era * 146_097 + doe - 719_468).ok()?;
+
+    Some(UNIX_EPOCH + Duration::from_secs(days * 86_400 + hours * 3_600 + minutes * 60 + seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(path: &str) -> Url {
+        Url::parse("http://localhost:5984").unwrap().join(path).unwrap()
+    }
+
+    #[test]
+    fn can_detect_idempotent_requests() {
+        assert!(is_idempotent(&Method::GET, &url("/db/doc")));
+        assert!(is_idempotent(&Method::HEAD, &url("/db")));
+        assert!(is_idempotent(&Method::POST, &url("/db/_all_docs")));
+        assert!(is_idempotent(&Method::POST, &url("/db/_find")));
+        assert!(is_idempotent(&Method::POST, &url("/db/_bulk_get")));
+        assert!(is_idempotent(&Method::POST, &url("/db/_revs_diff")));
+        assert!(is_idempotent(&Method::POST, &url("/db/_local_docs")));
+        assert!(is_idempotent(&Method::POST, &url("/db/_design_docs")));
+        assert!(is_idempotent(&Method::POST, &url("/db/_design/d/_view/v")));
+        assert!(is_idempotent(&Method::POST, &url("/db/_design/d/_view/v/queries")));
+        assert!(is_idempotent(&Method::POST, &url("/db/_all_docs/queries")));
+        assert!(!is_idempotent(&Method::POST, &url("/queries")));
+        assert!(!is_idempotent(&Method::POST, &url("/db/_design/d/_update/queries")));
+        assert!(!is_idempotent(&Method::POST, &url("/db/_bulk_docs")));
+        assert!(!is_idempotent(&Method::POST, &url("/db")));
+        assert!(!is_idempotent(&Method::PUT, &url("/db/doc")));
+        assert!(!is_idempotent(&Method::DELETE, &url("/db/doc")));
+    }
+
+    #[test]
+    fn can_back_off_exponentially() {
+        let policy = RetryPolicy::new(10)
+            .backoff(Duration::from_millis(100), Duration::from_secs(1))
+            .jitter(false);
+        assert_eq!(policy.delay(1), Duration::from_millis(100));
+        assert_eq!(policy.delay(2), Duration::from_millis(200));
+        assert_eq!(policy.delay(4), Duration::from_millis(800));
+        assert_eq!(policy.delay(5), Duration::from_secs(1));
+        assert_eq!(policy.delay(40), Duration::from_secs(1));
+
+        let policy = policy.jitter(true);
+        for attempt in 1..5 {
+            let delay = policy.delay(attempt);
+            let max = Duration::from_millis(100 << (attempt - 1));
+            assert!(delay >= max / 2 && delay <= max, "{delay:?} should be within {max:?}");
+        }
+    }
+
+    #[test]
+    fn honours_retry_after_up_to_the_max_backoff() {
+        let policy = RetryPolicy::new(3).backoff(Duration::from_millis(100), Duration::from_secs(10));
+        let unavailable = |retry_after: &str| -> CouchResult<Response> {
+            Ok(Response::from(
+                http::Response::builder()
+                    .status(StatusCode::SERVICE_UNAVAILABLE)
+                    .header(RETRY_AFTER, retry_after)
+                    .body("")
+                    .unwrap(),
+            ))
+        };
+
+        let (delay, _) = policy
+            .retry_after(&Method::GET, &url("/db"), 1, &unavailable("5"))
+            .expect("should retry");
+        assert_eq!(delay, Duration::from_secs(5));
+        assert!(policy
+            .retry_after(&Method::GET, &url("/db"), 1, &unavailable("60"))
+            .is_none());
+    }
+
+    #[test]
+    fn can_parse_http_dates() {
+        assert_eq!(
+            parse_http_date("Sun, 06 Nov 1994 08:49:37 GMT"),
+            Some(UNIX_EPOCH + Duration::from_secs(784_111_777))
+        );
+        assert_eq!(
+            parse_http_date("Thu, 29 Feb 2024 00:00:00 GMT"),
+            Some(UNIX_EPOCH + Duration::from_secs(1_709_164_800))
+        );
+        assert_eq!(parse_http_date("Sunday, 06-Nov-94 08:49:37 GMT"), None);
+        assert_eq!(parse_http_date("120"), None);
+    }
+}
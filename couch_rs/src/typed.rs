@@ -5,17 +5,28 @@ use crate::{
     document::{DocumentCollection, TypedCouchDocument},
     error::CouchResult,
     types::{
-        design::DesignCreated,
-        document::{DocumentCreatedResult, DocumentId},
+        attachment::{Attachment, AttachmentEncoding, AttachmentStream, DocumentWithAttachments},
+        bulk_get::BulkGetResult,
+        conflicts::{ConflictResolver, DocumentWithConflicts},
+        design::{DesignCreated, DesignDocument},
+        document::{DocumentCreatedResult, DocumentId, DocumentWritten},
         find::FindQuery,
         index::{DatabaseIndexList, IndexFields, IndexType},
+        options::{DocumentWithMeta, GetOptions, WriteOptions},
+        patch::Patch,
+        purge::PurgeResult,
         query::{QueriesParams, QueryParams},
+        revisions::{DocumentRevisions, OpenRevision, OpenRevs, RevsByDocument, RevsDiff},
+        security::SecurityObject,
+        update::{UpdateAction, UpdateResult},
         view::ViewCollection,
     },
 };
+use bytes::Bytes;
+use futures_core::TryStream;
 use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
-use std::marker::PhantomData;
+use std::{collections::HashMap, marker::PhantomData, time::Duration};
 use tokio::sync::mpsc::Sender;
 
 /// Wraps a database that will create/read/update/delete documents of a specific type.
@@ -60,6 +71,56 @@ impl<T: TypedCouchDocument> Database<T> {
         self.db.compact_index(index).await
     }
 
+    /// See [`Database::get_with_conflicts`](crate::database::Database::get_with_conflicts)
+    pub async fn get_with_conflicts(&self, id: &str) -> CouchResult<DocumentWithConflicts<T>> {
+        self.db.get_with_conflicts(id).await
+    }
+
+    /// See [`Database::resolve_conflicts`](crate::database::Database::resolve_conflicts)
+    pub async fn resolve_conflicts(&self, id: &str, resolver: &impl ConflictResolver<T>) -> CouchResult<T> {
+        self.db.resolve_conflicts(id, resolver).await
+    }
+
+    /// See [`Database::get_rev`](crate::database::Database::get_rev)
+    pub async fn get_rev(&self, id: &str, rev: &str) -> CouchResult<T> {
+        self.db.get_rev(id, rev).await
+    }
+
+    /// See [`Database::get_revisions`](crate::database::Database::get_revisions)
+    pub async fn get_revisions(&self, id: &str) -> CouchResult<DocumentRevisions<T>> {
+        self.db.get_revisions(id).await
+    }
+
+    /// See [`Database::get_open_revs`](crate::database::Database::get_open_revs)
+    pub async fn get_open_revs(&self, id: &str, open_revs: OpenRevs) -> CouchResult<Vec<OpenRevision<T>>> {
+        self.db.get_open_revs(id, open_revs).await
+    }
+
+    /// See [`Database::get_security`](crate::database::Database::get_security)
+    pub async fn get_security(&self) -> CouchResult<SecurityObject> {
+        self.db.get_security().await
+    }
+
+    /// See [`Database::set_security`](crate::database::Database::set_security)
+    pub async fn set_security(&self, security: &SecurityObject) -> CouchResult<()> {
+        self.db.set_security(security).await
+    }
+
+    /// See [`Database::add_member_role`](crate::database::Database::add_member_role)
+    pub async fn add_member_role(&self, role: &str) -> CouchResult<SecurityObject> {
+        self.db.add_member_role(role).await
+    }
+
+    /// See [`Database::remove_member_role`](crate::database::Database::remove_member_role)
+    pub async fn remove_member_role(&self, role: &str) -> CouchResult<SecurityObject> {
+        self.db.remove_member_role(role).await
+    }
+
+    /// See [`Database::wait_for_compaction`](crate::database::Database::wait_for_compaction)
+    pub async fn wait_for_compaction(&self, poll_interval: Duration, timeout: Duration) -> CouchResult<()> {
+        self.db.wait_for_compaction(poll_interval, timeout).await
+    }
+
     /// See [`Database::exists`](crate::database::Database::exists)
     pub async fn exists(&self, id: &str) -> bool {
         self.db.exists(id).await
@@ -70,16 +131,74 @@ impl<T: TypedCouchDocument> Database<T> {
         self.db.get(id).await
     }
 
+    /// See [`Database::get_with_options`](crate::database::Database::get_with_options)
+    pub async fn get_with_options(&self, id: &str, options: &GetOptions) -> CouchResult<DocumentWithMeta<T>> {
+        self.db.get_with_options(id, options).await
+    }
+
+    /// See [`Database::get_with_attachments`](crate::database::Database::get_with_attachments)
+    pub async fn get_with_attachments(&self, id: &str) -> CouchResult<DocumentWithAttachments<T>> {
+        self.db.get_with_attachments(id).await
+    }
+
     /// See [`Database::get_bulk`](crate::database::Database::get_bulk)
     pub async fn get_bulk(&self, ids: Vec<DocumentId>) -> CouchResult<DocumentCollection<T>> {
         self.db.get_bulk(ids).await
     }
 
+    /// See [`Database::bulk_get`](crate::database::Database::bulk_get)
+    pub async fn bulk_get(
+        &self,
+        docs: Vec<(DocumentId, Option<String>)>,
+        revs: bool,
+    ) -> CouchResult<Vec<BulkGetResult<T>>> {
+        self.db.bulk_get(docs, revs).await
+    }
+
+    /// See [`Database::revs_diff`](crate::database::Database::revs_diff)
+    pub async fn revs_diff(&self, revs: &RevsByDocument) -> CouchResult<HashMap<DocumentId, RevsDiff>> {
+        self.db.revs_diff(revs).await
+    }
+
+    /// See [`Database::missing_revs`](crate::database::Database::missing_revs)
+    pub async fn missing_revs(&self, revs: &RevsByDocument) -> CouchResult<RevsByDocument> {
+        self.db.missing_revs(revs).await
+    }
+
+    /// See [`Database::purge`](crate::database::Database::purge)
+    pub async fn purge(&self, revs: RevsByDocument) -> CouchResult<PurgeResult> {
+        self.db.purge(revs).await
+    }
+
+    /// See [`Database::purge_all_revisions`](crate::database::Database::purge_all_revisions)
+    pub async fn purge_all_revisions(&self, id: &str) -> CouchResult<PurgeResult> {
+        self.db.purge_all_revisions(id).await
+    }
+
+    /// See [`Database::purged_infos_limit`](crate::database::Database::purged_infos_limit)
+    pub async fn purged_infos_limit(&self) -> CouchResult<u64> {
+        self.db.purged_infos_limit().await
+    }
+
+    /// See [`Database::set_purged_infos_limit`](crate::database::Database::set_purged_infos_limit)
+    pub async fn set_purged_infos_limit(&self, limit: u64) -> CouchResult<()> {
+        self.db.set_purged_infos_limit(limit).await
+    }
+
     /// See [`Database::bulk_docs`](crate::database::Database::bulk_docs)
     pub async fn bulk_docs(&self, raw_docs: &mut [T]) -> CouchResult<Vec<DocumentCreatedResult>> {
         self.db.bulk_docs(raw_docs).await
     }
 
+    /// See [`Database::bulk_docs_with_options`](crate::database::Database::bulk_docs_with_options)
+    pub async fn bulk_docs_with_options(
+        &self,
+        raw_docs: &mut [T],
+        options: &WriteOptions,
+    ) -> CouchResult<Vec<DocumentCreatedResult>> {
+        self.db.bulk_docs_with_options(raw_docs, options).await
+    }
+
     /// See [`Database::get_bulk_params`](crate::database::Database::get_bulk_params)
     pub async fn get_bulk_params(
         &self,
@@ -148,16 +267,71 @@ impl<T: TypedCouchDocument> Database<T> {
         self.db.save(doc).await
     }
 
+    /// See [`Database::save_with_options`](crate::database::Database::save_with_options)
+    pub async fn save_with_options(&self, doc: &mut T, options: &WriteOptions) -> CouchResult<DocumentWritten> {
+        self.db.save_with_options(doc, options).await
+    }
+
     /// See [`Database::create`](crate::database::Database::create)
     pub async fn create(&self, doc: &mut T) -> DocumentCreatedResult {
         self.db.create(doc).await
     }
 
+    /// See [`Database::create_with_options`](crate::database::Database::create_with_options)
+    pub async fn create_with_options(&self, doc: &mut T, options: &WriteOptions) -> CouchResult<DocumentWritten> {
+        self.db.create_with_options(doc, options).await
+    }
+
+    /// See [`Database::save_with_attachments`](crate::database::Database::save_with_attachments)
+    pub async fn save_with_attachments(
+        &self,
+        doc: &mut T,
+        attachments: &HashMap<String, Attachment>,
+        encoding: AttachmentEncoding,
+    ) -> DocumentCreatedResult {
+        self.db.save_with_attachments(doc, attachments, encoding).await
+    }
+
+    /// See [`Database::create_with_attachments`](crate::database::Database::create_with_attachments)
+    pub async fn create_with_attachments(
+        &self,
+        doc: &mut T,
+        attachments: &HashMap<String, Attachment>,
+        encoding: AttachmentEncoding,
+    ) -> DocumentCreatedResult {
+        self.db.create_with_attachments(doc, attachments, encoding).await
+    }
+
     /// See [`Database::upsert`](crate::database::Database::upsert)
     pub async fn upsert(&self, doc: &mut T) -> DocumentCreatedResult {
         self.db.upsert(doc).await
     }
 
+    /// See [`Database::update`](crate::database::Database::update)
+    pub async fn update(&self, id: &str, change: impl FnMut(&mut T) -> UpdateAction) -> CouchResult<UpdateResult<T>> {
+        self.db.update(id, change).await
+    }
+
+    /// See [`Database::update_with_attempts`](crate::database::Database::update_with_attempts)
+    pub async fn update_with_attempts(
+        &self,
+        id: &str,
+        max_attempts: usize,
+        change: impl FnMut(&mut T) -> UpdateAction,
+    ) -> CouchResult<UpdateResult<T>> {
+        self.db.update_with_attempts(id, max_attempts, change).await
+    }
+
+    /// See [`Database::patch`](crate::database::Database::patch)
+    pub async fn patch(&self, id: &str, patch: &Patch) -> CouchResult<T> {
+        self.db.patch(id, patch).await
+    }
+
+    /// See [`Database::bulk_patch`](crate::database::Database::bulk_patch)
+    pub async fn bulk_patch(&self, patches: Vec<(DocumentId, Patch)>) -> CouchResult<Vec<DocumentCreatedResult>> {
+        self.db.bulk_patch(patches).await
+    }
+
     /// See [`Database::bulk_upsert`](crate::database::Database::bulk_upsert)
     pub async fn bulk_upsert(&self, docs: &mut [T]) -> CouchResult<Vec<DocumentCreatedResult>> {
         self.db.bulk_upsert(docs).await
@@ -168,6 +342,29 @@ impl<T: TypedCouchDocument> Database<T> {
         self.db.create_view(design_name, views).await
     }
 
+    /// See [`Database::get_design`](crate::database::Database::get_design)
+    pub async fn get_design(&self, design_name: &str) -> CouchResult<DesignDocument> {
+        self.db.get_design(design_name).await
+    }
+
+    /// See [`Database::put_design`](crate::database::Database::put_design)
+    pub async fn put_design(&self, design: &mut DesignDocument) -> DocumentCreatedResult {
+        self.db.put_design(design).await
+    }
+
+    /// See [`Database::delete_design`](crate::database::Database::delete_design)
+    pub async fn delete_design(&self, design_name: &str, rev: &str) -> CouchResult<String> {
+        self.db.delete_design(design_name, rev).await
+    }
+
+    /// See [`Database::list_design_docs`](crate::database::Database::list_design_docs)
+    pub async fn list_design_docs(
+        &self,
+        params: Option<QueryParams<DocumentId>>,
+    ) -> CouchResult<ViewCollection<DocumentId, Value, DesignDocument>> {
+        self.db.list_design_docs(params).await
+    }
+
     /// See [`Database::query`](crate::database::Database::query)
     pub async fn query<K: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug + Clone, V: DeserializeOwned>(
         &self,
@@ -190,10 +387,96 @@ impl<T: TypedCouchDocument> Database<T> {
     }
 
     /// See [`Database::remove`](crate::database::Database::remove)
-    pub async fn remove(&self, doc: &T) -> CouchResult<()> {
+    pub async fn remove(&self, doc: &T) -> CouchResult<String> {
         self.db.remove(doc).await
     }
 
+    /// See [`Database::remove_by_id`](crate::database::Database::remove_by_id)
+    pub async fn remove_by_id(&self, id: &str, rev: &str) -> CouchResult<String> {
+        self.db.remove_by_id(id, rev).await
+    }
+
+    /// See [`Database::bulk_remove`](crate::database::Database::bulk_remove)
+    pub async fn bulk_remove(&self, docs: &[T]) -> CouchResult<Vec<DocumentCreatedResult>> {
+        self.db.bulk_remove(docs).await
+    }
+
+    /// See [`Database::copy`](crate::database::Database::copy)
+    pub async fn copy(
+        &self,
+        source_id: &str,
+        source_rev: Option<&str>,
+        dest_id: &str,
+        dest_rev: Option<&str>,
+    ) -> DocumentCreatedResult {
+        self.db.copy(source_id, source_rev, dest_id, dest_rev).await
+    }
+
+    /// See [`Database::get_local`](crate::database::Database::get_local)
+    pub async fn get_local(&self, id: &str) -> CouchResult<T> {
+        self.db.get_local(id).await
+    }
+
+    /// See [`Database::save_local`](crate::database::Database::save_local)
+    pub async fn save_local(&self, doc: &mut T) -> DocumentCreatedResult {
+        self.db.save_local(doc).await
+    }
+
+    /// See [`Database::remove_local`](crate::database::Database::remove_local)
+    pub async fn remove_local(&self, doc: &T) -> CouchResult<()> {
+        self.db.remove_local(doc).await
+    }
+
+    /// See [`Database::list_local_docs`](crate::database::Database::list_local_docs)
+    pub async fn list_local_docs(
+        &self,
+        params: Option<QueryParams<DocumentId>>,
+    ) -> CouchResult<ViewCollection<DocumentId, Value, T>> {
+        self.db.list_local_docs(params).await
+    }
+
+    /// See [`Database::put_attachment`](crate::database::Database::put_attachment)
+    pub async fn put_attachment(
+        &self,
+        doc: &mut T,
+        name: &str,
+        content_type: &str,
+        data: impl Into<Bytes>,
+    ) -> DocumentCreatedResult {
+        self.db.put_attachment(doc, name, content_type, data).await
+    }
+
+    /// See [`Database::put_attachment_stream`](crate::database::Database::put_attachment_stream)
+    pub async fn put_attachment_stream<S>(
+        &self,
+        doc: &mut T,
+        name: &str,
+        content_type: &str,
+        stream: S,
+    ) -> DocumentCreatedResult
+    where
+        S: TryStream + Send + 'static,
+        S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+        Bytes: From<S::Ok>,
+    {
+        self.db.put_attachment_stream(doc, name, content_type, stream).await
+    }
+
+    /// See [`Database::get_attachment`](crate::database::Database::get_attachment)
+    pub async fn get_attachment(&self, id: &str, name: &str) -> CouchResult<Attachment> {
+        self.db.get_attachment(id, name).await
+    }
+
+    /// See [`Database::get_attachment_stream`](crate::database::Database::get_attachment_stream)
+    pub async fn get_attachment_stream(&self, id: &str, name: &str) -> CouchResult<AttachmentStream> {
+        self.db.get_attachment_stream(id, name).await
+    }
+
+    /// See [`Database::delete_attachment`](crate::database::Database::delete_attachment)
+    pub async fn delete_attachment(&self, doc: &mut T, name: &str) -> DocumentCreatedResult {
+        self.db.delete_attachment(doc, name).await
+    }
+
     /// See [`Database::insert_index`](crate::database::Database::insert_index)
     pub async fn insert_index(
         &self,
@@ -0,0 +1,139 @@
+use crate::{document::TypedCouchDocument, error::CouchResult};
+use bytes::Bytes;
+use futures_core::Stream;
+use futures_util::TryStreamExt;
+use std::{
+    collections::HashMap,
+    fmt, io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::io::AsyncRead;
+use tokio_util::io::StreamReader;
+
+/// A document attachment, fully loaded in memory.
+/// See [attachments](https://docs.couchdb.org/en/stable/api/document/attachments.html) for more details.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attachment {
+    /// MIME type of the attachment, as stored by `CouchDB`
+    pub content_type: String,
+    /// Base64-encoded MD5 digest of the attachment, when provided by `CouchDB`
+    pub digest: Option<String>,
+    /// The attachment's content
+    pub data: Bytes,
+}
+
+impl Attachment {
+    /// Creates a new attachment, to be sent along with a document.
+    #[must_use]
+    pub fn new(content_type: &str, data: impl Into<Bytes>) -> Self {
+        Attachment {
+            content_type: content_type.to_string(),
+            digest: None,
+            data: data.into(),
+        }
+    }
+}
+
+/// How attachments are sent to `CouchDB` when they are saved together with their document.
+/// See [inline attachments](https://docs.couchdb.org/en/stable/api/document/common.html#creating-multiple-attachments)
+/// for more details.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AttachmentEncoding {
+    /// Attachments are base64 encoded into the `_attachments` field of the document.
+    /// Simple, but adds a third to the size of the request; best suited for small attachments.
+    #[default]
+    Inline,
+    /// The document and its attachments are sent as separate parts of a `multipart/related` request.
+    Multipart,
+}
+
+/// A document together with the content of its attachments, keyed by attachment name.
+#[derive(Debug, Clone)]
+pub struct DocumentWithAttachments<T: TypedCouchDocument> {
+    pub doc: T,
+    pub attachments: HashMap<String, Attachment>,
+}
+
+/// Streaming access to an attachment's content, so large attachments do not have to be held in memory.
+///
+/// `AttachmentStream` implements `Stream<Item = CouchResult<Bytes>>`. Use `into_async_read` to consume it
+/// as a `tokio::io::AsyncRead` instead, for example to copy it into a file with `tokio::io::copy`.
+pub struct AttachmentStream {
+    /// MIME type of the attachment, as stored by `CouchDB`
+    pub content_type: String,
+    /// Base64-encoded MD5 digest of the attachment, when provided by `CouchDB`
+    pub digest: Option<String>,
+    /// Size of the attachment in bytes, when provided by `CouchDB`
+    pub content_length: Option<u64>,
+    inner: Pin<Box<dyn Stream<Item = CouchResult<Bytes>> + Send + Sync + 'static>>,
+}
+
+impl AttachmentStream {
+    pub(crate) fn new(
+        content_type: String,
+        digest: Option<String>,
+        content_length: Option<u64>,
+        inner: Pin<Box<dyn Stream<Item = CouchResult<Bytes>> + Send + Sync + 'static>>,
+    ) -> Self {
+        AttachmentStream {
+            content_type,
+            digest,
+            content_length,
+            inner,
+        }
+    }
+
+    /// Turns the stream into an `AsyncRead`.
+    pub fn into_async_read(self) -> impl AsyncRead + Send + Sync {
+        StreamReader::new(self.map_err(io::Error::other))
+    }
+}
+
+impl Stream for AttachmentStream {
+    type Item = CouchResult<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.inner.as_mut().poll_next(cx)
+    }
+}
+
+impl fmt::Debug for AttachmentStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AttachmentStream")
+            .field("content_type", &self.content_type)
+            .field("digest", &self.digest)
+            .field("content_length", &self.content_length)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Extracts the digest from an attachment `ETag` header; `CouchDB` sends the digest as a quoted string.
+pub(crate) fn digest_from_etag(etag: &str) -> String {
+    etag.trim_matches('"').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::stream;
+    use tokio::io::AsyncReadExt;
+
+    #[test]
+    fn can_strip_etag_quotes() {
+        assert_eq!(digest_from_etag(r#""md5-abc==""#), "md5-abc==");
+        assert_eq!(digest_from_etag("md5-abc=="), "md5-abc==");
+    }
+
+    #[tokio::test]
+    async fn can_read_stream_as_async_read() {
+        let chunks: Vec<CouchResult<Bytes>> = vec![Ok(Bytes::from_static(b"hello ")), Ok(Bytes::from_static(b"world"))];
+        let attachment =
+            AttachmentStream::new("text/plain".to_string(), None, Some(11), Box::pin(stream::iter(chunks)));
+
+        let mut reader = Box::pin(attachment.into_async_read());
+        let mut content = String::new();
+        reader.read_to_string(&mut content).await.expect("should read stream");
+        assert_eq!(content, "hello world");
+    }
+}
@@ -0,0 +1,194 @@
+use crate::{
+    document::{to_document, TypedCouchDocument, DELETED_FIELD, REV_FIELD},
+    error::{CouchError, CouchResult},
+    types::document::DocumentId,
+};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// The result for one of the documents requested from `_bulk_get`, see
+/// [`Database::bulk_get`](crate::database::Database::bulk_get).
+#[derive(Debug, Clone)]
+pub enum BulkGetResult<T: TypedCouchDocument> {
+    Found(T),
+    /// The document, or the requested revision, does not exist
+    NotFound {
+        id: DocumentId,
+        rev: Option<String>,
+    },
+    /// The document has been deleted; the revision is known when it was requested
+    Deleted {
+        id: DocumentId,
+        rev: Option<String>,
+    },
+    Error(CouchError),
+}
+
+impl<T: TypedCouchDocument> BulkGetResult<T> {
+    /// The document, when it was found.
+    #[must_use]
+    pub fn found(self) -> Option<T> {
+        match self {
+            BulkGetResult::Found(doc) => Some(doc),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub(crate) struct BulkGetRequest {
+    pub docs: Vec<BulkGetDoc>,
+}
+
+#[derive(Serialize, Debug, Clone)]
+pub(crate) struct BulkGetDoc {
+    pub id: DocumentId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct BulkGetResponse {
+    pub results: Vec<BulkGetResponseItem>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub(crate) struct BulkGetResponseItem {
+    pub id: DocumentId,
+    #[serde(default)]
+    pub docs: Vec<Value>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct BulkGetError {
+    rev: Option<String>,
+    error: String,
+    #[serde(default)]
+    reason: String,
+}
+
+impl BulkGetResponseItem {
+    /// Converts the item into a result; a single revision was requested, so only the first entry is used.
+    pub(crate) fn into_result<T: TypedCouchDocument>(mut self) -> CouchResult<BulkGetResult<T>> {
+        let Some(entry) = self.docs.first_mut().map(Value::take) else {
+            return Ok(BulkGetResult::NotFound { id: self.id, rev: None });
+        };
+
+        if let Some(doc) = entry.get("ok").cloned() {
+            if doc.get(DELETED_FIELD) == Some(&Value::Bool(true)) {
+                let rev = doc.get(REV_FIELD).and_then(Value::as_str).map(str::to_string);
+                return Ok(BulkGetResult::Deleted { id: self.id, rev });
+            }
+            // a document that does not fit `T` only fails its own entry
+            return Ok(match to_document(doc) {
+                Ok(doc) => BulkGetResult::Found(doc),
+                Err(err) => BulkGetResult::Error(err),
+            });
+        }
+
+        let error: BulkGetError = match entry.get("error") {
+            Some(error) => serde_json::from_value(error.clone())?,
+            None => {
+                return Err(CouchError::new(
+                    format!("unexpected _bulk_get entry: {entry}"),
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                ))
+            }
+        };
+        // the generated "undefined" revision stands for "no revision requested"
+        let rev = error.rev.filter(|rev| rev != "undefined");
+        Ok(match (error.error.as_str(), error.reason.as_str()) {
+            ("not_found", "deleted") => BulkGetResult::Deleted { id: self.id, rev },
+            ("not_found", _) => BulkGetResult::NotFound { id: self.id, rev },
+            (error, reason) => {
+                let status = match error {
+                    "forbidden" => StatusCode::FORBIDDEN,
+                    "unauthorized" => StatusCode::UNAUTHORIZED,
+                    "bad_request" => StatusCode::BAD_REQUEST,
+                    _ => StatusCode::INTERNAL_SERVER_ERROR,
+                };
+                BulkGetResult::Error(CouchError::new_with_id(
+                    Some(self.id),
+                    format!("{error}: {reason}"),
+                    status,
+                ))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn can_convert_bulk_get_response() {
+        let response: BulkGetResponse = serde_json::from_value(json!({
+            "results": [
+                {"id": "found", "docs": [{"ok": {"_id": "found", "_rev": "1-a", "v": 1}}]},
+                {"id": "missing", "docs": [{"error": {"id": "missing", "rev": "undefined", "error": "not_found", "reason": "missing"}}]},
+                {"id": "gone", "docs": [{"error": {"id": "gone", "rev": "undefined", "error": "not_found", "reason": "deleted"}}]},
+                {"id": "gone", "docs": [{"ok": {"_id": "gone", "_rev": "2-b", "_deleted": true}}]},
+                {"id": "bad", "docs": [{"error": {"id": "bad", "rev": "x", "error": "bad_request", "reason": "Invalid rev format"}}]}
+            ]
+        }))
+        .unwrap();
+
+        let results: Vec<BulkGetResult<Value>> = response
+            .results
+            .into_iter()
+            .map(|item| item.into_result().unwrap())
+            .collect();
+        assert!(matches!(&results[0], BulkGetResult::Found(doc) if doc["v"] == 1));
+        assert!(matches!(&results[1], BulkGetResult::NotFound { id, rev: None } if id == "missing"));
+        assert!(matches!(&results[2], BulkGetResult::Deleted { rev: None, .. }));
+        assert!(matches!(&results[3], BulkGetResult::Deleted { rev: Some(rev), .. } if rev == "2-b"));
+        assert!(matches!(&results[4], BulkGetResult::Error(err) if err.status() == Some(StatusCode::BAD_REQUEST)));
+    }
+
+    #[test]
+    fn reports_documents_that_do_not_deserialize() {
+        #[derive(Serialize, Deserialize, Debug, Clone)]
+        struct Counter {
+            _id: String,
+            _rev: String,
+            count: u32,
+        }
+        impl TypedCouchDocument for Counter {
+            fn get_id(&self) -> std::borrow::Cow<'_, str> {
+                std::borrow::Cow::from(&self._id)
+            }
+            fn get_rev(&self) -> std::borrow::Cow<'_, str> {
+                std::borrow::Cow::from(&self._rev)
+            }
+            fn set_rev(&mut self, rev: &str) {
+                self._rev = rev.to_string();
+            }
+            fn set_id(&mut self, id: &str) {
+                self._id = id.to_string();
+            }
+            fn merge_ids(&mut self, other: &Self) {
+                self._id.clone_from(&other._id);
+                self._rev.clone_from(&other._rev);
+            }
+        }
+
+        let response: BulkGetResponse = serde_json::from_value(json!({
+            "results": [
+                {"id": "ok", "docs": [{"ok": {"_id": "ok", "_rev": "1-a", "count": 1}}]},
+                {"id": "bad", "docs": [{"ok": {"_id": "bad", "_rev": "1-b", "count": "one"}}]}
+            ]
+        }))
+        .unwrap();
+
+        let results: Vec<BulkGetResult<Counter>> = response
+            .results
+            .into_iter()
+            .map(|item| item.into_result().unwrap())
+            .collect();
+        assert!(matches!(&results[0], BulkGetResult::Found(doc) if doc.count == 1));
+        assert!(matches!(&results[1], BulkGetResult::Error(CouchError::InvalidJson(_))));
+    }
+}
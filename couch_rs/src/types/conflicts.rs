@@ -0,0 +1,147 @@
+use crate::document::TypedCouchDocument;
+use serde_json::Value;
+
+/// A document together with its conflicting revisions.
+/// See [replication and conflicts](https://docs.couchdb.org/en/stable/replication/conflicts.html) for more details.
+#[derive(Debug, Clone)]
+pub struct DocumentWithConflicts<T: TypedCouchDocument> {
+    /// The winning revision, as chosen by `CouchDB`
+    pub doc: T,
+    /// The conflicting revisions, that lost to the winning revision
+    pub conflicts: Vec<T>,
+}
+
+impl<T: TypedCouchDocument> DocumentWithConflicts<T> {
+    #[must_use]
+    pub fn has_conflicts(&self) -> bool {
+        !self.conflicts.is_empty()
+    }
+}
+
+/// Resolves the conflicts of a document, see [`Database::resolve_conflicts`](crate::database::Database::resolve_conflicts).
+///
+/// Any `Fn(&T, &[T]) -> T` closure is a resolver, which makes custom merges straightforward:
+/// ```
+/// use couch_rs::types::conflicts::ConflictResolver;
+/// use serde_json::Value;
+///
+/// // keep the revision with the latest "updated_at" timestamp
+/// let resolver = |winner: &Value, conflicts: &[Value]| {
+///     conflicts
+///         .iter()
+///         .chain(std::iter::once(winner))
+///         .max_by_key(|doc| doc["updated_at"].as_str().unwrap_or_default().to_string())
+///         .cloned()
+///         .unwrap_or_else(|| winner.clone())
+/// };
+/// ```
+pub trait ConflictResolver<T: TypedCouchDocument>: Send + Sync {
+    /// Merges the winning revision and the conflicting revisions into the document that replaces all of them.
+    /// The `_id` and `_rev` of the result are taken care of by `resolve_conflicts`.
+    fn resolve(&self, winner: &T, conflicts: &[T]) -> T;
+}
+
+impl<T, F> ConflictResolver<T> for F
+where
+    T: TypedCouchDocument,
+    F: Fn(&T, &[T]) -> T + Send + Sync,
+{
+    fn resolve(&self, winner: &T, conflicts: &[T]) -> T {
+        self(winner, conflicts)
+    }
+}
+
+/// Keeps the revision with the latest timestamp in the given field, like an ISO 8601 `"updated_at"` string or a
+/// number of milliseconds since the epoch. Revisions without a timestamp lose; on a tie `CouchDB`'s winner is kept.
+///
+/// `CouchDB` itself picks its winner by the length of the edit history, not by time, so the winner is not
+/// necessarily the latest write.
+/// ```
+/// use couch_rs::types::conflicts::LastWriteWins;
+/// let _resolver = LastWriteWins::new("updated_at");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LastWriteWins {
+    field: String,
+}
+
+impl LastWriteWins {
+    #[must_use]
+    pub fn new(field: &str) -> Self {
+        LastWriteWins {
+            field: field.to_string(),
+        }
+    }
+
+    fn timestamp(&self, doc: &impl TypedCouchDocument) -> Option<Value> {
+        serde_json::to_value(doc)
+            .ok()?
+            .get_mut(&self.field)
+            .map(Value::take)
+            .filter(|timestamp| timestamp.is_string() || timestamp.is_number())
+    }
+}
+
+impl<T: TypedCouchDocument + Clone> ConflictResolver<T> for LastWriteWins {
+    fn resolve(&self, winner: &T, conflicts: &[T]) -> T {
+        let mut latest = winner;
+        let mut latest_timestamp = self.timestamp(winner);
+        for conflict in conflicts {
+            let timestamp = self.timestamp(conflict);
+            if is_later(timestamp.as_ref(), latest_timestamp.as_ref()) {
+                latest = conflict;
+                latest_timestamp = timestamp;
+            }
+        }
+        latest.clone()
+    }
+}
+
+/// Compares numbers by value and strings lexicographically; a timestamp of another type is never later.
+fn is_later(timestamp: Option<&Value>, than: Option<&Value>) -> bool {
+    match (timestamp, than) {
+        (Some(Value::Number(a)), Some(Value::Number(b))) => a.as_f64() > b.as_f64(),
+        (Some(Value::String(a)), Some(Value::String(b))) => a > b,
+        (Some(_), None) => true,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn last_write_wins_keeps_the_latest_timestamp() {
+        let resolver = LastWriteWins::new("updated_at");
+        let winner = json!({"_id": "1", "_rev": "10-aaa", "updated_at": "2024-01-02T00:00:00Z", "v": "winner"});
+        let conflicts = vec![
+            json!({"_id": "1", "_rev": "3-bbb", "updated_at": "2024-03-01T00:00:00Z", "v": "latest"}),
+            json!({"_id": "1", "_rev": "9-ccc", "v": "untimed"}),
+        ];
+        assert_eq!(resolver.resolve(&winner, &conflicts)["v"], "latest");
+        assert_eq!(resolver.resolve(&winner, &conflicts[1..])["v"], "winner");
+        assert_eq!(resolver.resolve(&conflicts[1], &conflicts)["v"], "latest");
+        assert_eq!(resolver.resolve(&winner, &[])["v"], "winner");
+
+        let millis = LastWriteWins::new("ts");
+        let winner = json!({"_id": "1", "_rev": "2-a", "ts": 1_700_000_000_000_u64});
+        let later = json!({"_id": "1", "_rev": "1-b", "ts": 1_700_000_000_001_u64});
+        assert_eq!(millis.resolve(&winner, std::slice::from_ref(&later)), later);
+    }
+
+    #[test]
+    fn closures_are_resolvers() {
+        let merge = |winner: &Value, conflicts: &[Value]| {
+            let total: i64 = conflicts
+                .iter()
+                .chain(Some(winner))
+                .filter_map(|d| d["count"].as_i64())
+                .sum();
+            json!({ "count": total })
+        };
+        let merged = merge.resolve(&json!({"count": 1}), &[json!({"count": 2}), json!({"count": 3})]);
+        assert_eq!(merged["count"], 6);
+    }
+}
@@ -1,5 +1,11 @@
-use crate::error::{CouchError, CouchResult, ErrorMessage};
-use serde::{Deserialize, Serialize};
+use crate::{
+    document::{TypedCouchDocument, DESIGN_PREFIX},
+    error::{CouchError, CouchResult, ErrorMessage},
+    types::{document::DocumentId, view::CouchFunc},
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::{Map, Value};
+use std::{borrow::Cow, collections::HashMap};
 
 /// Design document created abstraction
 #[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
@@ -38,3 +44,253 @@ impl DesignCreated {
         }
     }
 }
+
+/// A design document with its functions, see [design documents](https://docs.couchdb.org/en/stable/ddocs/ddocs.html).
+/// Functions are given as `JavaScript` source. Mango indexes, which are design documents with the `query` language,
+/// are managed through [`Database::insert_index`](crate::database::Database::insert_index) instead; they can still be
+/// read as a `DesignDocument`, with their views as [`DesignView::Other`]. Fields that are not modelled, like
+/// `_attachments` or `rewrites`, are kept in `other`, so they survive a round trip.
+/// ```
+/// use couch_rs::types::design::DesignDocument;
+/// use couch_rs::types::view::CouchFunc;
+///
+/// let mut design = DesignDocument::new("reports");
+/// design.views.insert(
+///     "by_date".to_string(),
+///     CouchFunc::new("function (doc) { emit(doc.date, null); }", Some("_count")).into(),
+/// );
+/// design.validate_doc_update =
+///     Some("function (newDoc) { if (!newDoc.date) { throw({forbidden: 'a date is required'}); } }".to_string());
+/// assert_eq!(design.name(), "reports");
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct DesignDocument {
+    /// The id, like `_design/reports`
+    #[serde(rename = "_id")]
+    pub id: DocumentId,
+
+    #[serde(rename = "_rev", skip_serializing_if = "String::is_empty", default)]
+    pub rev: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+
+    #[serde(skip_serializing_if = "HashMap::is_empty", default)]
+    pub views: HashMap<String, DesignView>,
+
+    /// Update functions by name, see [`Database::execute_update`](crate::database::Database::execute_update)
+    #[serde(skip_serializing_if = "HashMap::is_empty", default)]
+    pub updates: HashMap<String, String>,
+
+    /// Filter functions by name, for the changes feed and replication
+    #[serde(skip_serializing_if = "HashMap::is_empty", default)]
+    pub filters: HashMap<String, String>,
+
+    /// Validates every document write; throws `{forbidden: ...}` or `{unauthorized: ...}` to reject it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validate_doc_update: Option<String>,
+
+    #[serde(skip_serializing_if = "HashMap::is_empty", default)]
+    pub shows: HashMap<String, String>,
+
+    #[serde(skip_serializing_if = "HashMap::is_empty", default)]
+    pub lists: HashMap<String, String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub options: Option<DesignOptions>,
+
+    /// With `false`, the views are not built in the background, only when they are queried
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub autoupdate: Option<bool>,
+
+    /// All other fields of the design document
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// A view of a design document.
+#[derive(Serialize, PartialEq, Eq, Debug, Clone)]
+#[serde(untagged)]
+pub enum DesignView {
+    /// A view with a map function and an optional reduce function
+    Func(CouchFunc),
+    /// Any other view, like the index of a Mango design document or the `lib` with `CommonJS` modules, as it is
+    Other(Value),
+}
+
+impl DesignView {
+    /// The map and reduce functions, when this is a [`DesignView::Func`].
+    #[must_use]
+    pub fn as_func(&self) -> Option<&CouchFunc> {
+        match self {
+            DesignView::Func(func) => Some(func),
+            DesignView::Other(_) => None,
+        }
+    }
+}
+
+impl From<CouchFunc> for DesignView {
+    fn from(func: CouchFunc) -> Self {
+        DesignView::Func(func)
+    }
+}
+
+impl<'de> Deserialize<'de> for DesignView {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = Value::deserialize(deserializer)?;
+        // only views with nothing but the functions are read as a `CouchFunc`, so no field is lost
+        let functions_only = value
+            .as_object()
+            .is_some_and(|view| view.keys().all(|key| key == "map" || key == "reduce"));
+        if functions_only {
+            if let Ok(func) = serde_json::from_value(value.clone()) {
+                return Ok(DesignView::Func(func));
+            }
+        }
+        Ok(DesignView::Other(value))
+    }
+}
+
+/// Options of a design document, that apply to all of its views.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct DesignOptions {
+    /// Whether the views are partitioned; design documents of a partitioned database are partitioned by default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub partitioned: Option<bool>,
+
+    /// Makes the sequence of the documents available to the map functions, as `_local_seq`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_seq: Option<bool>,
+
+    /// Also passes design documents to the map functions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub include_design: Option<bool>,
+
+    /// All other options
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl DesignDocument {
+    /// A design document with the given name, using `JavaScript` functions.
+    #[must_use]
+    pub fn new(name: &str) -> Self {
+        DesignDocument {
+            id: format!("{DESIGN_PREFIX}{name}"),
+            language: Some("javascript".to_string()),
+            ..DesignDocument::default()
+        }
+    }
+
+    /// The name of the design document: the id without the `_design/` prefix.
+    #[must_use]
+    pub fn name(&self) -> &str {
+        self.id.strip_prefix(DESIGN_PREFIX).unwrap_or(&self.id)
+    }
+}
+
+impl From<DesignDocument> for Value {
+    fn from(d: DesignDocument) -> Self {
+        serde_json::to_value(d).unwrap()
+    }
+}
+
+impl TypedCouchDocument for DesignDocument {
+    fn get_id(&self) -> Cow<'_, str> {
+        Cow::from(&self.id)
+    }
+
+    fn get_rev(&self) -> Cow<'_, str> {
+        Cow::from(&self.rev)
+    }
+
+    fn set_rev(&mut self, rev: &str) {
+        self.rev = rev.to_string();
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.id = id.to_string();
+    }
+
+    fn merge_ids(&mut self, other: &Self) {
+        self.id.clone_from(&other.id);
+        self.rev.clone_from(&other.rev);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn can_serialize_design_document() {
+        let mut design = DesignDocument::new("reports");
+        design.filters.insert(
+            "important".to_string(),
+            "function (doc) { return doc.important; }".to_string(),
+        );
+        design.options = Some(DesignOptions {
+            partitioned: Some(false),
+            ..DesignOptions::default()
+        });
+
+        assert_eq!(
+            serde_json::to_value(&design).unwrap(),
+            json!({
+                "_id": "_design/reports",
+                "language": "javascript",
+                "filters": {"important": "function (doc) { return doc.important; }"},
+                "options": {"partitioned": false}
+            })
+        );
+    }
+
+    #[test]
+    fn can_deserialize_design_document() {
+        let design: DesignDocument = serde_json::from_value(json!({
+            "_id": "_design/reports",
+            "_rev": "2-abc",
+            "views": {"by_date": {"map": "function (doc) { emit(doc.date); }", "reduce": "_count"}},
+            "updates": {"touch": "function (doc, req) { return [doc, 'ok']; }"},
+            "autoupdate": false
+        }))
+        .unwrap();
+
+        assert_eq!(design.name(), "reports");
+        assert_eq!(design.get_rev(), "2-abc");
+        assert_eq!(
+            design.views["by_date"].as_func().unwrap().reduce.as_deref(),
+            Some("_count")
+        );
+        assert!(design.updates.contains_key("touch"));
+        assert_eq!(design.autoupdate, Some(false));
+        assert_eq!(design.language, None);
+    }
+
+    #[test]
+    fn keeps_unmodelled_fields_of_design_documents() {
+        let raw = json!({
+            "_id": "_design/idx",
+            "_rev": "1-abc",
+            "language": "query",
+            "views": {
+                "by_date": {
+                    "map": {"fields": {"date": "asc"}, "partial_filter_selector": {}},
+                    "reduce": "_count",
+                    "options": {"def": {"fields": ["date"]}}
+                },
+                "lib": {"utils": "exports.id = function (doc) { return doc._id; };"},
+                "with_options": {"map": "function (doc) { emit(doc._id); }", "options": {"collation": "raw"}}
+            },
+            "rewrites": [{"from": "/", "to": "index.html"}],
+            "options": {"partitioned": false, "collation": "raw"}
+        });
+        let design: DesignDocument = serde_json::from_value(raw.clone()).unwrap();
+
+        assert!(design.views.values().all(|view| matches!(view, DesignView::Other(_))));
+        assert!(design.other.contains_key("rewrites"));
+        assert!(design.options.as_ref().unwrap().other.contains_key("collation"));
+        assert_eq!(serde_json::to_value(&design).unwrap(), raw);
+    }
+}
@@ -33,7 +33,7 @@ pub(crate) struct DocumentCreatedResponse {
 pub struct DocumentCreatedDetails {
     /// Document ID
     pub id: String,
-    /// New document revision token.
+    /// New document revision token
     pub rev: String,
 }
 
@@ -65,3 +65,31 @@ impl From<DocumentCreatedResponse> for DocumentCreatedResult {
 }
 
 pub type DocumentCreatedResult = CouchResult<DocumentCreatedDetails>;
+
+/// Outcome of a document write with [`WriteOptions`](crate::types::options::WriteOptions)
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug, Clone)]
+pub enum DocumentWritten {
+    /// The document is stored with a new revision
+    Created(DocumentCreatedDetails),
+    /// The write was accepted in batch mode and is stored later, so there is no revision yet
+    Accepted { id: String },
+}
+
+impl DocumentWritten {
+    #[must_use]
+    pub fn id(&self) -> &str {
+        match self {
+            DocumentWritten::Created(details) => &details.id,
+            DocumentWritten::Accepted { id } => id,
+        }
+    }
+
+    /// The new revision; `None` when the write was accepted in batch mode
+    #[must_use]
+    pub fn rev(&self) -> Option<&str> {
+        match self {
+            DocumentWritten::Created(details) => Some(&details.rev),
+            DocumentWritten::Accepted { .. } => None,
+        }
+    }
+}
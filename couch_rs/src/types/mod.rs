@@ -1,9 +1,21 @@
+pub mod attachment;
+pub mod bulk_get;
 pub mod changes;
+pub mod conflicts;
 pub mod design;
 pub mod design_info;
 pub mod document;
 pub mod find;
 pub mod index;
+pub mod options;
+pub mod patch;
+pub mod purge;
 pub mod query;
+pub mod replication;
+pub mod revisions;
+pub mod security;
 pub mod system;
+pub mod tasks;
+pub mod update;
+pub mod user;
 pub mod view;
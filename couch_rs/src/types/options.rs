@@ -0,0 +1,276 @@
+use crate::{document::TypedCouchDocument, error::CouchResult, types::revisions::RevisionInfo};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Options for reading a single document. You can use the builder paradigm to construct these options easily:
+/// [document.html](https://docs.couchdb.org/en/stable/api/document/common.html#get--db-docid)
+/// ```
+/// use couch_rs::types::options::GetOptions;
+/// let _options = GetOptions::default().local_seq(true).deleted_conflicts(true).r(2);
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct GetOptions {
+    /// Includes the encoding information of compressed attachments
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub att_encoding_info: Option<bool>,
+
+    /// Includes the attachments that changed since the given revisions
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub atts_since: Vec<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub conflicts: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted_conflicts: Option<bool>,
+
+    /// Returns the latest leaf revision instead of the requested `rev`, when it has been updated since
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub local_seq: Option<bool>,
+
+    /// Shorthand for `conflicts`, `deleted_conflicts` and `revs_info`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub meta: Option<bool>,
+
+    /// Read quorum: the number of replicas that must respond
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub r: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rev: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revs_info: Option<bool>,
+}
+
+impl GetOptions {
+    #[must_use]
+    pub fn att_encoding_info(mut self, att_encoding_info: bool) -> Self {
+        self.att_encoding_info = Some(att_encoding_info);
+        self
+    }
+
+    #[must_use]
+    pub fn atts_since(mut self, atts_since: Vec<String>) -> Self {
+        self.atts_since = atts_since;
+        self
+    }
+
+    #[must_use]
+    pub fn conflicts(mut self, conflicts: bool) -> Self {
+        self.conflicts = Some(conflicts);
+        self
+    }
+
+    #[must_use]
+    pub fn deleted_conflicts(mut self, deleted_conflicts: bool) -> Self {
+        self.deleted_conflicts = Some(deleted_conflicts);
+        self
+    }
+
+    #[must_use]
+    pub fn latest(mut self, latest: bool) -> Self {
+        self.latest = Some(latest);
+        self
+    }
+
+    #[must_use]
+    pub fn local_seq(mut self, local_seq: bool) -> Self {
+        self.local_seq = Some(local_seq);
+        self
+    }
+
+    #[must_use]
+    pub fn meta(mut self, meta: bool) -> Self {
+        self.meta = Some(meta);
+        self
+    }
+
+    #[must_use]
+    pub fn r(mut self, r: u32) -> Self {
+        self.r = Some(r);
+        self
+    }
+
+    #[must_use]
+    pub fn rev(mut self, rev: &str) -> Self {
+        self.rev = Some(rev.to_string());
+        self
+    }
+
+    #[must_use]
+    pub fn revs_info(mut self, revs_info: bool) -> Self {
+        self.revs_info = Some(revs_info);
+        self
+    }
+
+    /// The options as query parameters; `atts_since` is sent as a JSON array.
+    pub(crate) fn to_params(&self) -> CouchResult<HashMap<String, String>> {
+        let mut params = HashMap::new();
+        if let Value::Object(options) = serde_json::to_value(self)? {
+            for (name, value) in options {
+                let value = match value {
+                    Value::String(value) => value,
+                    value => value.to_string(),
+                };
+                params.insert(name, value);
+            }
+        }
+        Ok(params)
+    }
+}
+
+/// Options for writing documents. You can use the builder paradigm to construct these options easily:
+/// ```
+/// use couch_rs::types::options::WriteOptions;
+/// let _telemetry = WriteOptions::default().batch(true);
+/// let _migration = WriteOptions::default().new_edits(false);
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct WriteOptions {
+    /// Sends `batch=ok`: `CouchDB` accepts the write before it is committed to disk, and replies without a
+    /// revision. Faster, but the write may be lost. Applies to single document writes, not to `_bulk_docs`
+    pub batch: bool,
+
+    /// With `false`, the revisions of the documents are stored as given instead of new revisions being
+    /// assigned, as replication does. The documents must have a `_rev`
+    pub new_edits: Option<bool>,
+}
+
+impl WriteOptions {
+    #[must_use]
+    pub fn batch(mut self, batch: bool) -> Self {
+        self.batch = batch;
+        self
+    }
+
+    #[must_use]
+    pub fn new_edits(mut self, new_edits: bool) -> Self {
+        self.new_edits = Some(new_edits);
+        self
+    }
+
+    /// The options as query parameters of a single document write.
+    pub(crate) fn to_params(&self) -> HashMap<String, String> {
+        let mut params = HashMap::new();
+        if self.batch {
+            params.insert("batch".to_string(), "ok".to_string());
+        }
+        if let Some(new_edits) = self.new_edits {
+            params.insert("new_edits".to_string(), new_edits.to_string());
+        }
+        params
+    }
+}
+
+/// Options for creating a database, see [`Client::create_db_with`](crate::Client::create_db_with).
+/// ```
+/// use couch_rs::types::options::CreateDbOptions;
+/// let _options = CreateDbOptions::default().partitioned(true).q(16);
+/// ```
+#[derive(PartialEq, Eq, Debug, Clone, Default)]
+pub struct CreateDbOptions {
+    /// The number of shards; the server default when not set
+    pub q: Option<u32>,
+
+    /// The number of replicas of each shard; the server default when not set
+    pub n: Option<u32>,
+
+    /// Creates a [partitioned](https://docs.couchdb.org/en/stable/partitioned-dbs/index.html) database, where
+    /// document ids have the form `partition:docid`
+    pub partitioned: bool,
+}
+
+impl CreateDbOptions {
+    #[must_use]
+    pub fn q(mut self, q: u32) -> Self {
+        self.q = Some(q);
+        self
+    }
+
+    #[must_use]
+    pub fn n(mut self, n: u32) -> Self {
+        self.n = Some(n);
+        self
+    }
+
+    #[must_use]
+    pub fn partitioned(mut self, partitioned: bool) -> Self {
+        self.partitioned = partitioned;
+        self
+    }
+
+    /// The options as query parameters of the database creation.
+    pub(crate) fn to_params(&self) -> HashMap<String, String> {
+        let mut params = HashMap::new();
+        if let Some(q) = self.q {
+            params.insert("q".to_string(), q.to_string());
+        }
+        if let Some(n) = self.n {
+            params.insert("n".to_string(), n.to_string());
+        }
+        if self.partitioned {
+            params.insert("partitioned".to_string(), "true".to_string());
+        }
+        params
+    }
+}
+
+/// A document together with the metadata requested through [`GetOptions`].
+#[derive(Debug, Clone)]
+pub struct DocumentWithMeta<T: TypedCouchDocument> {
+    pub doc: T,
+    /// The sequence of the last update of the document; from `_local_seq`
+    pub local_seq: Option<Value>,
+    /// The availability of each revision, newest first; from `_revs_info`
+    pub revs_info: Vec<RevisionInfo>,
+    /// The conflicting revisions; from `_conflicts`
+    pub conflicts: Vec<String>,
+    /// The conflicting revisions that have been deleted; from `_deleted_conflicts`
+    pub deleted_conflicts: Vec<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_convert_options_to_params() {
+        let params = GetOptions::default()
+            .latest(true)
+            .rev("2-abc")
+            .atts_since(vec!["1-aaa".to_string()])
+            .r(2)
+            .to_params()
+            .unwrap();
+
+        assert_eq!(params.len(), 4);
+        assert_eq!(params["latest"], "true");
+        assert_eq!(params["rev"], "2-abc");
+        assert_eq!(params["atts_since"], r#"["1-aaa"]"#);
+        assert_eq!(params["r"], "2");
+        assert!(GetOptions::default().to_params().unwrap().is_empty());
+    }
+
+    #[test]
+    fn can_convert_write_options_to_params() {
+        let params = WriteOptions::default().batch(true).new_edits(false).to_params();
+        assert_eq!(params["batch"], "ok");
+        assert_eq!(params["new_edits"], "false");
+        assert!(WriteOptions::default().to_params().is_empty());
+    }
+
+    #[test]
+    fn can_convert_create_db_options_to_params() {
+        let params = CreateDbOptions::default().q(8).n(1).partitioned(true).to_params();
+        assert_eq!(params.len(), 3);
+        assert_eq!(params["q"], "8");
+        assert_eq!(params["n"], "1");
+        assert_eq!(params["partitioned"], "true");
+        assert!(CreateDbOptions::default().to_params().is_empty());
+    }
+}
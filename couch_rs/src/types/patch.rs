@@ -0,0 +1,312 @@
+use crate::{
+    document::{ID_FIELD, REV_FIELD},
+    error::{CouchError, CouchResult, ErrorMessage},
+};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// A change to a document, see [`Database::patch`](crate::database::Database::patch).
+/// ```
+/// use couch_rs::types::patch::{Patch, PatchOperation};
+/// use serde_json::json;
+///
+/// let mut doc = json!({"name": "John", "tags": ["a"]});
+/// let patch = Patch::Json(vec![
+///     PatchOperation::Test { path: "/name".to_string(), value: json!("John") },
+///     PatchOperation::Add { path: "/tags/-".to_string(), value: json!("b") },
+/// ]);
+/// patch.apply(&mut doc).unwrap();
+/// Patch::Merge(json!({"name": "Jane"})).apply(&mut doc).unwrap();
+/// assert_eq!(doc, json!({"name": "Jane", "tags": ["a", "b"]}));
+/// ```
+#[derive(PartialEq, Debug, Clone)]
+pub enum Patch {
+    /// A [JSON Patch](https://www.rfc-editor.org/rfc/rfc6902): operations that are applied in order, all or none
+    Json(Vec<PatchOperation>),
+    /// A [JSON Merge Patch](https://www.rfc-editor.org/rfc/rfc7396): an object whose fields replace those of the
+    /// document, where `null` removes a field
+    Merge(Value),
+}
+
+/// A JSON Patch operation; paths are JSON Pointers like `/address/street` or `/tags/0`.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum PatchOperation {
+    Add {
+        path: String,
+        value: Value,
+    },
+    Remove {
+        path: String,
+    },
+    Replace {
+        path: String,
+        value: Value,
+    },
+    Move {
+        from: String,
+        path: String,
+    },
+    Copy {
+        from: String,
+        path: String,
+    },
+    /// Fails the whole patch with [`CouchError::PatchTestFailed`] when the value differs
+    /// or the path does not exist
+    Test {
+        path: String,
+        value: Value,
+    },
+}
+
+impl Patch {
+    /// Applies the patch to a JSON value. A JSON Patch that fails leaves the value unchanged.
+    pub fn apply(&self, doc: &mut Value) -> CouchResult<()> {
+        match self {
+            Patch::Json(operations) => {
+                let mut patched = doc.clone();
+                for operation in operations {
+                    apply_operation(&mut patched, operation)?;
+                }
+                *doc = patched;
+            }
+            Patch::Merge(patch) => merge(doc, patch),
+        }
+        Ok(())
+    }
+
+    /// Applies the patch to a document; its `_id` and `_rev` are kept as they are.
+    pub(crate) fn apply_to_document(&self, doc: &mut Value) -> CouchResult<()> {
+        let id = doc.get(ID_FIELD).cloned();
+        let rev = doc.get(REV_FIELD).cloned();
+        self.apply(doc)?;
+        if let Value::Object(fields) = doc {
+            for (field, value) in [(ID_FIELD, id), (REV_FIELD, rev)] {
+                match value {
+                    Some(value) => fields.insert(field.to_string(), value),
+                    None => fields.remove(field),
+                };
+            }
+            Ok(())
+        } else {
+            Err(invalid("a patched document must be an object"))
+        }
+    }
+}
+
+fn apply_operation(doc: &mut Value, operation: &PatchOperation) -> CouchResult<()> {
+    match operation {
+        PatchOperation::Add { path, value } => add(doc, path, value.clone()),
+        PatchOperation::Remove { path } => remove(doc, path).map(|_| ()),
+        PatchOperation::Replace { path, value } => {
+            let target = pointer_mut(doc, path)?;
+            *target = value.clone();
+            Ok(())
+        }
+        PatchOperation::Move { from, path } => {
+            if path.starts_with(&format!("{from}/")) {
+                return Err(invalid(&format!("can not move {from} into itself")));
+            }
+            let value = remove(doc, from)?;
+            add(doc, path, value)
+        }
+        PatchOperation::Copy { from, path } => {
+            let value = pointer_mut(doc, from)?.clone();
+            add(doc, path, value)
+        }
+        PatchOperation::Test { path, value } => {
+            if !path.is_empty() && !path.starts_with('/') {
+                return Err(invalid(&format!("invalid path: {path}")));
+            }
+            match doc.pointer(path) {
+                Some(current) if current == value => Ok(()),
+                Some(_) => Err(test_failed(format!("test failed: {path} is not {value}"))),
+                None => Err(test_failed(format!("test failed: {path} does not exist"))),
+            }
+        }
+    }
+}
+
+fn add(doc: &mut Value, path: &str, value: Value) -> CouchResult<()> {
+    let Some((parent, token)) = split_pointer(path)? else {
+        *doc = value;
+        return Ok(());
+    };
+    match pointer_mut(doc, &parent)? {
+        Value::Object(fields) => {
+            fields.insert(token, value);
+            Ok(())
+        }
+        Value::Array(items) => {
+            let index = if token == "-" {
+                items.len()
+            } else {
+                array_index(&token, items.len() + 1, path)?
+            };
+            items.insert(index, value);
+            Ok(())
+        }
+        _ => Err(invalid(&format!(
+            "can not add {path} to a value that is not an object or array"
+        ))),
+    }
+}
+
+fn remove(doc: &mut Value, path: &str) -> CouchResult<Value> {
+    let Some((parent, token)) = split_pointer(path)? else {
+        return Err(invalid("can not remove the whole document"));
+    };
+    match pointer_mut(doc, &parent)? {
+        Value::Object(fields) => fields.remove(&token).ok_or_else(|| not_found(path)),
+        Value::Array(items) => {
+            let index = array_index(&token, items.len(), path)?;
+            Ok(items.remove(index))
+        }
+        _ => Err(not_found(path)),
+    }
+}
+
+/// Resolves a JSON Pointer to the value it refers to.
+fn pointer_mut<'a>(doc: &'a mut Value, path: &str) -> CouchResult<&'a mut Value> {
+    if !path.is_empty() && !path.starts_with('/') {
+        return Err(invalid(&format!("invalid path: {path}")));
+    }
+    doc.pointer_mut(path).ok_or_else(|| not_found(path))
+}
+
+/// Splits a JSON Pointer into the pointer of the parent and the unescaped last token; `None` for the root.
+fn split_pointer(path: &str) -> CouchResult<Option<(String, String)>> {
+    if path.is_empty() {
+        return Ok(None);
+    }
+    match path.rsplit_once('/') {
+        Some((parent, token)) => Ok(Some((parent.to_string(), token.replace("~1", "/").replace("~0", "~")))),
+        None => Err(invalid(&format!("invalid path: {path}"))),
+    }
+}
+
+/// Parses an array index, which must be below `bound`.
+fn array_index(token: &str, bound: usize, path: &str) -> CouchResult<usize> {
+    let valid =
+        !token.is_empty() && token.bytes().all(|b| b.is_ascii_digit()) && (token == "0" || !token.starts_with('0'));
+    match token.parse::<usize>() {
+        Ok(index) if valid && index < bound => Ok(index),
+        _ => Err(not_found(path)),
+    }
+}
+
+fn merge(doc: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *doc = patch.clone();
+        return;
+    };
+    if !doc.is_object() {
+        *doc = Value::Object(Map::new());
+    }
+    if let Value::Object(fields) = doc {
+        for (field, value) in patch {
+            if value.is_null() {
+                fields.remove(field);
+            } else {
+                merge(fields.entry(field.as_str()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
+
+fn invalid(message: &str) -> CouchError {
+    CouchError::new(message.to_string(), StatusCode::UNPROCESSABLE_ENTITY)
+}
+
+fn test_failed(message: String) -> CouchError {
+    CouchError::PatchTestFailed(ErrorMessage {
+        message,
+        upstream: None,
+    })
+}
+
+fn not_found(path: &str) -> CouchError {
+    invalid(&format!("path does not exist: {path}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn json_patch(operations: Value) -> Patch {
+        Patch::Json(serde_json::from_value(operations).unwrap())
+    }
+
+    #[test]
+    fn can_apply_json_patch() {
+        let mut doc = json!({"baz": "qux", "foo": {"bar": ["a", "c"]}, "a~b": 1});
+        json_patch(json!([
+            {"op": "add", "path": "/foo/bar/1", "value": "b"},
+            {"op": "replace", "path": "/baz", "value": "boo"},
+            {"op": "move", "from": "/a~0b", "path": "/moved"},
+            {"op": "copy", "from": "/foo/bar/0", "path": "/foo/bar/-"},
+            {"op": "remove", "path": "/foo/bar/2"},
+            {"op": "test", "path": "/moved", "value": 1}
+        ]))
+        .apply(&mut doc)
+        .unwrap();
+
+        assert_eq!(doc, json!({"baz": "boo", "foo": {"bar": ["a", "b", "a"]}, "moved": 1}));
+    }
+
+    #[test]
+    fn failed_json_patch_leaves_document_unchanged() {
+        let original = json!({"foo": ["a"]});
+        let mut doc = original.clone();
+
+        let err = json_patch(json!([
+            {"op": "add", "path": "/bar", "value": 1},
+            {"op": "test", "path": "/foo/0", "value": "b"}
+        ]))
+        .apply(&mut doc)
+        .unwrap_err();
+        assert!(matches!(err, CouchError::PatchTestFailed(_)));
+        assert_eq!(doc, original);
+
+        let err = json_patch(json!([{"op": "test", "path": "/missing", "value": 1}]))
+            .apply(&mut doc)
+            .unwrap_err();
+        assert!(matches!(err, CouchError::PatchTestFailed(_)));
+
+        for operations in [
+            json!([{"op": "remove", "path": "/missing"}]),
+            json!([{"op": "replace", "path": "/foo/1", "value": 1}]),
+            json!([{"op": "add", "path": "/foo/01", "value": 1}]),
+            json!([{"op": "move", "from": "/foo", "path": "/foo/0"}]),
+            json!([{"op": "add", "path": "foo", "value": 1}]),
+        ] {
+            let err = json_patch(operations).apply(&mut doc).unwrap_err();
+            assert_eq!(err.status(), Some(StatusCode::UNPROCESSABLE_ENTITY));
+        }
+        assert_eq!(doc, original);
+    }
+
+    #[test]
+    fn can_apply_merge_patch() {
+        let mut doc = json!({"title": "Goodbye!", "author": {"givenName": "John", "familyName": "Doe"}, "tags": ["example", "sample"], "content": "This will be unchanged"});
+        Patch::Merge(json!({"title": "Hello!", "phoneNumber": "+01-123-456-7890", "author": {"familyName": null}, "tags": ["example"]}))
+            .apply(&mut doc)
+            .unwrap();
+
+        assert_eq!(
+            doc,
+            json!({"title": "Hello!", "author": {"givenName": "John"}, "tags": ["example"], "content": "This will be unchanged", "phoneNumber": "+01-123-456-7890"})
+        );
+    }
+
+    #[test]
+    fn keeps_id_and_rev_of_document() {
+        let mut doc = json!({"_id": "1", "_rev": "1-a", "v": 1});
+        Patch::Merge(json!({"_id": "2", "_rev": null, "v": 2}))
+            .apply_to_document(&mut doc)
+            .unwrap();
+        assert_eq!(doc, json!({"_id": "1", "_rev": "1-a", "v": 2}));
+    }
+}
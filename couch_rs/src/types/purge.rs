@@ -0,0 +1,12 @@
+use crate::types::revisions::RevsByDocument;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Response of a purge, see [`Database::purge`](crate::database::Database::purge).
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct PurgeResult {
+    /// Not returned by clustered databases, use the `purge_seq` of the database info instead
+    pub purge_seq: Option<Value>,
+    /// The revisions that were purged, by document id
+    pub purged: RevsByDocument,
+}
@@ -0,0 +1,430 @@
+use crate::document::{TypedCouchDocument, ID_FIELD};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::{borrow::Cow, collections::HashMap};
+
+/// Source or target database of a replication.
+/// See [replication](https://docs.couchdb.org/en/stable/api/server/common.html#replicate) for more details.
+///
+/// `CouchDB` requires full URLs for both local and remote databases, for example
+/// `http://localhost:5984/db`. Both the plain URL and the object form are read; the object form is written.
+/// ```
+/// use couch_rs::types::replication::{ReplicationAuth, ReplicationEndpoint};
+/// let _source = ReplicationEndpoint::new("https://example.com/db").auth(ReplicationAuth::basic("admin", "password"));
+/// let _target: ReplicationEndpoint = "http://localhost:5984/db".into();
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(from = "EndpointForm")]
+pub struct ReplicationEndpoint {
+    pub url: String,
+
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auth: Option<ReplicationAuth>,
+}
+
+impl ReplicationEndpoint {
+    #[must_use]
+    pub fn new(url: &str) -> Self {
+        ReplicationEndpoint {
+            url: url.to_string(),
+            headers: HashMap::new(),
+            auth: None,
+        }
+    }
+
+    /// Sets the credentials used to access this endpoint.
+    #[must_use]
+    pub fn auth(mut self, auth: ReplicationAuth) -> Self {
+        self.auth = Some(auth);
+        self
+    }
+
+    /// Adds a header that is sent with every request to this endpoint.
+    #[must_use]
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        self.headers.insert(name.to_string(), value.to_string());
+        self
+    }
+}
+
+/// The forms of an endpoint in replication documents: `"http://..."` or `{"url": "http://...", ...}`.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum EndpointForm {
+    Url(String),
+    Object {
+        url: String,
+        #[serde(default)]
+        headers: HashMap<String, String>,
+        auth: Option<ReplicationAuth>,
+    },
+}
+
+impl From<EndpointForm> for ReplicationEndpoint {
+    fn from(form: EndpointForm) -> Self {
+        match form {
+            EndpointForm::Url(url) => ReplicationEndpoint::new(&url),
+            EndpointForm::Object { url, headers, auth } => ReplicationEndpoint { url, headers, auth },
+        }
+    }
+}
+
+impl From<&str> for ReplicationEndpoint {
+    fn from(url: &str) -> Self {
+        ReplicationEndpoint::new(url)
+    }
+}
+
+impl From<String> for ReplicationEndpoint {
+    fn from(url: String) -> Self {
+        ReplicationEndpoint::new(&url)
+    }
+}
+
+/// Credentials used by the replicator to access an endpoint.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum ReplicationAuth {
+    Basic { username: String, password: String },
+}
+
+impl ReplicationAuth {
+    #[must_use]
+    pub fn basic(username: &str, password: &str) -> Self {
+        ReplicationAuth::Basic {
+            username: username.to_string(),
+            password: password.to_string(),
+        }
+    }
+}
+
+/// Replication options. You can use the builder paradigm to construct these options easily:
+/// [replication](https://docs.couchdb.org/en/stable/json-structure.html#replication-settings)
+/// ```
+/// use couch_rs::types::replication::ReplicationOptions;
+/// use serde_json::json;
+/// let _options = ReplicationOptions::default()
+///     .continuous(true)
+///     .create_target(true)
+///     .selector(json!({"type": "invoice"}));
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone, Default)]
+pub struct ReplicationOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub continuous: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancel: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub create_target: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub create_target_params: Option<Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub query_params: Option<Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc_ids: Option<Vec<String>>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since_seq: Option<Value>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checkpoint_interval: Option<u64>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub use_checkpoints: Option<bool>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub winning_revs_only: Option<bool>,
+}
+
+impl ReplicationOptions {
+    /// Keeps replicating new changes, until the replication is cancelled.
+    #[must_use]
+    pub fn continuous(mut self, continuous: bool) -> Self {
+        self.continuous = Some(continuous);
+        self
+    }
+
+    /// Cancels the (continuous) replication with the same source, target and options.
+    #[must_use]
+    pub fn cancel(mut self, cancel: bool) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    /// Creates the target database when it does not exist.
+    #[must_use]
+    pub fn create_target(mut self, create_target: bool) -> Self {
+        self.create_target = Some(create_target);
+        self
+    }
+
+    /// Only replicates documents accepted by the filter function, e.g. `ddoc/filter`.
+    #[must_use]
+    pub fn filter(mut self, filter: &str, query_params: Option<Value>) -> Self {
+        self.filter = Some(filter.to_string());
+        self.query_params = query_params;
+        self
+    }
+
+    /// Only replicates the documents with the given ids.
+    #[must_use]
+    pub fn doc_ids(mut self, doc_ids: Vec<String>) -> Self {
+        self.doc_ids = Some(doc_ids);
+        self
+    }
+
+    /// Only replicates the documents matching the `_find` selector.
+    #[must_use]
+    pub fn selector(mut self, selector: Value) -> Self {
+        self.selector = Some(selector);
+        self
+    }
+
+    /// Starts replicating from the given update sequence, instead of the last checkpoint.
+    #[must_use]
+    pub fn since_seq(mut self, since_seq: Value) -> Self {
+        self.since_seq = Some(since_seq);
+        self
+    }
+
+    /// Sets the interval between checkpoints, in milliseconds.
+    #[must_use]
+    pub fn checkpoint_interval(mut self, checkpoint_interval: u64) -> Self {
+        self.checkpoint_interval = Some(checkpoint_interval);
+        self
+    }
+}
+
+/// Result of a replication through `_replicate`.
+/// For continuous replications only `ok` and `local_id` are set.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ReplicationResult {
+    pub ok: bool,
+
+    #[serde(rename = "_local_id")]
+    pub local_id: Option<String>,
+
+    pub session_id: Option<String>,
+
+    pub source_last_seq: Option<Value>,
+
+    pub replication_id_version: Option<u32>,
+
+    #[serde(default)]
+    pub no_changes: bool,
+
+    #[serde(default)]
+    pub history: Vec<ReplicationHistory>,
+}
+
+/// Statistics of a replication session.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ReplicationHistory {
+    pub session_id: String,
+    pub start_time: Option<String>,
+    pub end_time: Option<String>,
+    pub start_last_seq: Option<Value>,
+    pub end_last_seq: Option<Value>,
+    pub recorded_seq: Option<Value>,
+    #[serde(default)]
+    pub docs_read: u64,
+    #[serde(default)]
+    pub docs_written: u64,
+    #[serde(default)]
+    pub doc_write_failures: u64,
+    #[serde(default)]
+    pub missing_checked: u64,
+    #[serde(default)]
+    pub missing_found: u64,
+}
+
+/// A persistent replication, stored in the `_replicator` database.
+/// See [replicator database](https://docs.couchdb.org/en/stable/replication/replicator.html) for more details.
+///
+/// The `_replication_*` fields are maintained by `CouchDB` and are never sent. Fields that are not modelled here,
+/// like `user_ctx` or `owner`, are kept in `other`, so they survive a get and save.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ReplicationDocument {
+    #[serde(rename = "_id", skip_serializing_if = "String::is_empty", default)]
+    pub id: String,
+
+    #[serde(rename = "_rev", skip_serializing_if = "String::is_empty", default)]
+    pub rev: String,
+
+    pub source: ReplicationEndpoint,
+
+    pub target: ReplicationEndpoint,
+
+    #[serde(flatten)]
+    pub options: ReplicationOptions,
+
+    #[serde(rename = "_replication_state", skip_serializing)]
+    pub replication_state: Option<String>,
+
+    #[serde(rename = "_replication_state_time", skip_serializing)]
+    pub replication_state_time: Option<String>,
+
+    #[serde(rename = "_replication_state_reason", skip_serializing)]
+    pub replication_state_reason: Option<String>,
+
+    #[serde(rename = "_replication_stats", skip_serializing)]
+    pub replication_stats: Option<Value>,
+
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl ReplicationDocument {
+    #[must_use]
+    pub fn new(
+        id: &str,
+        source: impl Into<ReplicationEndpoint>,
+        target: impl Into<ReplicationEndpoint>,
+        options: ReplicationOptions,
+    ) -> Self {
+        ReplicationDocument {
+            id: id.to_string(),
+            rev: String::new(),
+            source: source.into(),
+            target: target.into(),
+            options,
+            replication_state: None,
+            replication_state_time: None,
+            replication_state_reason: None,
+            replication_stats: None,
+            other: Map::new(),
+        }
+    }
+}
+
+impl TypedCouchDocument for ReplicationDocument {
+    fn get_id(&self) -> Cow<'_, str> {
+        Cow::from(&self.id)
+    }
+
+    fn get_rev(&self) -> Cow<'_, str> {
+        Cow::from(&self.rev)
+    }
+
+    fn set_rev(&mut self, rev: &str) {
+        self.rev = rev.to_string();
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.id = id.to_string();
+    }
+
+    fn merge_ids(&mut self, other: &Self) {
+        self.set_id(&other.get_id());
+        self.set_rev(&other.get_rev());
+    }
+}
+
+/// The body of a `_replicate` request.
+#[derive(Serialize)]
+pub(crate) struct ReplicationRequest<'a> {
+    pub source: &'a ReplicationEndpoint,
+    pub target: &'a ReplicationEndpoint,
+    #[serde(flatten)]
+    pub options: &'a ReplicationOptions,
+}
+
+/// Whether a document in the `_replicator` database describes a replication; design documents do not.
+pub(crate) fn is_replication_document(doc: &Value) -> bool {
+    let id = doc.get(ID_FIELD).and_then(Value::as_str).unwrap_or_default();
+    !id.starts_with("_design/") && doc.get("source").is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn can_serialize_replication_request() {
+        let source = ReplicationEndpoint::new("https://example.com/a").auth(ReplicationAuth::basic("admin", "secret"));
+        let target = ReplicationEndpoint::from("http://localhost:5984/b");
+        let options = ReplicationOptions::default()
+            .create_target(true)
+            .doc_ids(vec!["1".to_string()])
+            .checkpoint_interval(5000);
+
+        let request = ReplicationRequest {
+            source: &source,
+            target: &target,
+            options: &options,
+        };
+        assert_eq!(
+            serde_json::to_value(&request).unwrap(),
+            json!({
+                "source": {"url": "https://example.com/a", "auth": {"basic": {"username": "admin", "password": "secret"}}},
+                "target": {"url": "http://localhost:5984/b"},
+                "create_target": true,
+                "doc_ids": ["1"],
+                "checkpoint_interval": 5000
+            })
+        );
+    }
+
+    #[test]
+    fn can_deserialize_replication_document() {
+        let doc: ReplicationDocument = serde_json::from_value(json!({
+            "_id": "my_rep",
+            "_rev": "1-abc",
+            "source": {"url": "http://localhost:5984/a"},
+            "target": {"url": "http://localhost:5984/b"},
+            "continuous": true,
+            "_replication_state": "completed"
+        }))
+        .unwrap();
+
+        assert_eq!(doc.id, "my_rep");
+        assert_eq!(doc.options.continuous, Some(true));
+        assert_eq!(doc.replication_state.as_deref(), Some("completed"));
+
+        let value = serde_json::to_value(&doc).unwrap();
+        assert!(value.get("_replication_state").is_none());
+        assert!(doc.other.is_empty());
+        assert!(is_replication_document(&value));
+        assert!(!is_replication_document(
+            &json!({"_id": "_design/_replicator", "_rev": "1-a"})
+        ));
+    }
+
+    #[test]
+    fn keeps_url_endpoints_and_unknown_fields() {
+        let doc: ReplicationDocument = serde_json::from_value(json!({
+            "_id": "fauxton_rep",
+            "source": "http://localhost:5984/a",
+            "target": {"url": "http://localhost:5984/b", "headers": {"x-app": "test"}},
+            "filter": "ddoc/important",
+            "owner": "admin",
+            "user_ctx": {"name": "admin", "roles": ["_admin"]}
+        }))
+        .unwrap();
+
+        assert_eq!(doc.source, ReplicationEndpoint::new("http://localhost:5984/a"));
+        assert_eq!(doc.target.headers["x-app"], "test");
+        assert_eq!(doc.options.filter.as_deref(), Some("ddoc/important"));
+        assert_eq!(doc.other.len(), 2);
+
+        let value = serde_json::to_value(&doc).unwrap();
+        assert_eq!(value["owner"], "admin");
+        assert_eq!(value["user_ctx"]["roles"], json!(["_admin"]));
+        assert_eq!(value["filter"], "ddoc/important");
+    }
+}
@@ -0,0 +1,152 @@
+use crate::{document::TypedCouchDocument, types::document::DocumentId};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A document together with its revision history.
+/// See [revisions](https://docs.couchdb.org/en/stable/api/document/common.html#obtaining-an-extended-revision-history)
+/// for more details.
+#[derive(Debug, Clone)]
+pub struct DocumentRevisions<T: TypedCouchDocument> {
+    pub doc: T,
+    /// The revisions of the document, newest first; from `_revisions`
+    pub revisions: Vec<String>,
+    /// The availability of each revision, newest first; from `_revs_info`
+    pub revs_info: Vec<RevisionInfo>,
+}
+
+/// Availability of a revision of a document.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct RevisionInfo {
+    pub rev: String,
+    pub status: RevisionStatus,
+}
+
+/// Whether the content of a revision is still available; older revisions are removed by compaction.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+#[serde(rename_all = "snake_case")]
+pub enum RevisionStatus {
+    Available,
+    Missing,
+    Deleted,
+}
+
+/// The `_revisions` field: the revision hashes, newest first, with the generation of the newest revision.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct Revisions {
+    pub start: u64,
+    pub ids: Vec<String>,
+}
+
+impl Revisions {
+    /// The full revisions, like `3-917fa2381192822767f010b95b45325b`, newest first.
+    #[must_use]
+    pub fn revs(&self) -> Vec<String> {
+        (0..=self.start)
+            .rev()
+            .zip(&self.ids)
+            .map(|(generation, id)| format!("{generation}-{id}"))
+            .collect()
+    }
+}
+
+/// Which leaf revisions to get, see [`Database::get_open_revs`](crate::database::Database::get_open_revs).
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum OpenRevs {
+    /// All leaf revisions: the winner, the conflicts and the deleted leaves
+    All,
+    /// The given revisions
+    Specific(Vec<String>),
+}
+
+/// A revision returned by `open_revs`.
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum OpenRevision<T: TypedCouchDocument> {
+    Found(T),
+    /// A revision that ends in a deletion
+    Deleted(String),
+    /// A requested revision that does not exist
+    Missing(String),
+}
+
+/// Revisions by document id, as sent to and returned by `_revs_diff` and `_missing_revs`.
+pub type RevsByDocument = HashMap<DocumentId, Vec<String>>;
+
+/// The revisions of a document that are missing from the database, see
+/// [`Database::revs_diff`](crate::database::Database::revs_diff).
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct RevsDiff {
+    pub missing: Vec<String>,
+    /// Revisions the database does have, that may be ancestors of the missing revisions
+    #[serde(default)]
+    pub possible_ancestors: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub(crate) struct MissingRevs {
+    pub missing_revs: RevsByDocument,
+}
+
+/// Splits the revisions into chunks of at most `size` revisions, keeping the revisions of a document together.
+pub(crate) fn chunk_revs(revs: &RevsByDocument, size: usize) -> Vec<RevsByDocument> {
+    let mut chunks = vec![];
+    let mut chunk = RevsByDocument::new();
+    let mut chunk_len = 0;
+    for (id, doc_revs) in revs {
+        if chunk_len > 0 && chunk_len + doc_revs.len() > size {
+            chunks.push(std::mem::take(&mut chunk));
+            chunk_len = 0;
+        }
+        chunk_len += doc_revs.len();
+        chunk.insert(id.clone(), doc_revs.clone());
+    }
+    if !chunk.is_empty() {
+        chunks.push(chunk);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn can_expand_revisions() {
+        let revisions: Revisions = serde_json::from_value(json!({
+            "ids": ["e10b", "5c8d", "1f4e"],
+            "start": 3
+        }))
+        .unwrap();
+        assert_eq!(revisions.revs(), vec!["3-e10b", "2-5c8d", "1-1f4e"]);
+    }
+
+    #[test]
+    fn can_deserialize_revs_info() {
+        let revs_info: Vec<RevisionInfo> = serde_json::from_value(json!([
+            {"rev": "2-5c8d", "status": "available"},
+            {"rev": "1-1f4e", "status": "missing"}
+        ]))
+        .unwrap();
+        assert_eq!(revs_info[1].status, RevisionStatus::Missing);
+    }
+
+    #[test]
+    fn can_chunk_revs() {
+        let mut revs = RevsByDocument::new();
+        revs.insert("a".to_string(), vec!["1-a".to_string(), "2-a".to_string()]);
+        revs.insert("b".to_string(), vec!["1-b".to_string()]);
+        revs.insert(
+            "c".to_string(),
+            vec!["1-c".to_string(), "2-c".to_string(), "3-c".to_string()],
+        );
+
+        let chunks = chunk_revs(&revs, 2);
+        assert_eq!(chunks.iter().map(HashMap::len).sum::<usize>(), 3);
+        // a document with more revisions than the chunk size gets a chunk of its own
+        assert!(chunks.iter().any(|chunk| chunk.len() == 1 && chunk.contains_key("c")));
+        assert!(chunks
+            .iter()
+            .all(|chunk| chunk.contains_key("c") || chunk.values().map(Vec::len).sum::<usize>() <= 2));
+        assert!(chunk_revs(&RevsByDocument::new(), 2).is_empty());
+    }
+}
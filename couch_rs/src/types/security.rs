@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Security object of a database, which controls who can read and write it.
+/// See [_security](https://docs.couchdb.org/en/stable/api/database/security.html) for more details.
+///
+/// Fields that are not known to this crate are preserved when the object is written back.
+/// ```
+/// use couch_rs::types::security::SecurityObject;
+/// let mut security = SecurityObject::default();
+/// security.admins.add_name("tenant_admin");
+/// security.members.add_role("tenant");
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct SecurityObject {
+    /// Admins can read and write all documents, including design documents, and change the security object
+    #[serde(default)]
+    pub admins: SecurityGroup,
+
+    /// Members can read and write all documents, except design documents. When there are no members, the
+    /// database is public
+    #[serde(default)]
+    pub members: SecurityGroup,
+
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// Users and roles that belong to the admins or members of a database.
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone, Default)]
+pub struct SecurityGroup {
+    #[serde(default)]
+    pub names: Vec<String>,
+
+    #[serde(default)]
+    pub roles: Vec<String>,
+}
+
+impl SecurityGroup {
+    /// Adds a user name; returns false when it was already present.
+    pub fn add_name(&mut self, name: &str) -> bool {
+        add(&mut self.names, name)
+    }
+
+    /// Removes a user name; returns false when it was not present.
+    pub fn remove_name(&mut self, name: &str) -> bool {
+        remove(&mut self.names, name)
+    }
+
+    /// Adds a role; returns false when it was already present.
+    pub fn add_role(&mut self, role: &str) -> bool {
+        add(&mut self.roles, role)
+    }
+
+    /// Removes a role; returns false when it was not present.
+    pub fn remove_role(&mut self, role: &str) -> bool {
+        remove(&mut self.roles, role)
+    }
+}
+
+fn add(values: &mut Vec<String>, value: &str) -> bool {
+    if values.iter().any(|v| v == value) {
+        false
+    } else {
+        values.push(value.to_string());
+        true
+    }
+}
+
+fn remove(values: &mut Vec<String>, value: &str) -> bool {
+    let len = values.len();
+    values.retain(|v| v != value);
+    values.len() != len
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn can_round_trip_security_object() {
+        let mut security: SecurityObject = serde_json::from_value(json!({
+            "admins": {"names": ["superuser"], "roles": []},
+            "members": {"roles": ["developers"]},
+            "couchdb_auth_only": true
+        }))
+        .unwrap();
+
+        assert!(security.members.names.is_empty());
+        assert!(security.members.add_role("tenant"));
+        assert!(!security.members.add_role("tenant"));
+        assert!(security.members.remove_role("developers"));
+        assert!(!security.members.remove_role("developers"));
+
+        assert_eq!(
+            serde_json::to_value(&security).unwrap(),
+            json!({
+                "admins": {"names": ["superuser"], "roles": []},
+                "members": {"names": [], "roles": ["tenant"]},
+                "couchdb_auth_only": true
+            })
+        );
+    }
+
+    #[test]
+    fn can_read_empty_security_object() {
+        let security: SecurityObject = serde_json::from_value(json!({})).unwrap();
+        assert_eq!(security, SecurityObject::default());
+    }
+}
@@ -45,7 +45,7 @@ pub struct SizeInfo {
 /// Database information
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DbProperties {
-    partitioned: Option<bool>,
+    pub partitioned: Option<bool>,
 }
 
 /// Database information
@@ -63,3 +63,20 @@ pub struct DbInfo {
     pub update_seq: String,
     pub props: DbProperties,
 }
+
+/// Size information of a partition
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PartitionSizeInfo {
+    pub active: u64,
+    pub external: u64,
+}
+
+/// Partition information
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PartitionInfo {
+    pub db_name: String,
+    pub partition: String,
+    pub doc_count: u64,
+    pub doc_del_count: u64,
+    pub sizes: PartitionSizeInfo,
+}
@@ -0,0 +1,35 @@
+use crate::document::TypedCouchDocument;
+
+/// What to do with a document after it has been modified by the closure passed to
+/// [`Database::update`](crate::database::Database::update).
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub enum UpdateAction {
+    /// Save the modified document
+    Save,
+    /// Leave the document as it is
+    Abort,
+    /// Delete the document
+    Delete,
+}
+
+/// The outcome of [`Database::update`](crate::database::Database::update).
+#[derive(PartialEq, Eq, Debug, Clone)]
+pub enum UpdateResult<T: TypedCouchDocument> {
+    /// The document as saved, with its new revision
+    Saved(T),
+    /// The document as stored, unmodified
+    Aborted(T),
+    /// The document was deleted; holds the revision of the deletion
+    Deleted(String),
+}
+
+impl<T: TypedCouchDocument> UpdateResult<T> {
+    /// The saved document, if any.
+    #[must_use]
+    pub fn saved(self) -> Option<T> {
+        match self {
+            UpdateResult::Saved(doc) => Some(doc),
+            UpdateResult::Aborted(_) | UpdateResult::Deleted(_) => None,
+        }
+    }
+}
@@ -0,0 +1,187 @@
+use crate::document::TypedCouchDocument;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::borrow::Cow;
+
+/// Prefix of the document ids in the `_users` database.
+pub const USER_ID_PREFIX: &str = "org.couchdb.user:";
+
+const USER_TYPE: &str = "user";
+
+/// A user document in the `_users` database.
+/// See [users database](https://docs.couchdb.org/en/stable/intro/security.html#users-documents) for more details.
+///
+/// When the id is empty, it is derived from the name as `org.couchdb.user:<name>`, so the document can be saved
+/// through the [`Client`](crate::Client) helpers, a [`Database`](crate::database::Database), or a
+/// [`typed::Database<CouchUser>`](crate::typed::Database) for the `_users` database alike.
+///
+/// `CouchDB` replaces the `password` by the `derived_key`, `salt`, `iterations` and `password_scheme` fields;
+/// these are sent back unchanged on an update, which keeps the current password.
+/// ```
+/// use couch_rs::types::user::CouchUser;
+/// let user = CouchUser::new("jan", "apple", vec!["tenant".to_string()]);
+/// ```
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
+pub struct CouchUser {
+    #[serde(rename = "_id", skip_serializing_if = "String::is_empty", default)]
+    pub id: String,
+
+    #[serde(rename = "_rev", skip_serializing_if = "String::is_empty", default)]
+    pub rev: String,
+
+    pub name: String,
+
+    #[serde(default)]
+    pub roles: Vec<String>,
+
+    /// Always `user`
+    #[serde(rename = "type")]
+    pub user_type: String,
+
+    /// The new password in plain text; `CouchDB` never returns it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub derived_key: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub salt: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iterations: Option<u32>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub password_scheme: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pbkdf2_prf: Option<String>,
+
+    /// Any other fields stored in the user document
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+impl CouchUser {
+    #[must_use]
+    pub fn new(name: &str, password: &str, roles: Vec<String>) -> Self {
+        CouchUser {
+            id: user_id(name),
+            rev: String::new(),
+            name: name.to_string(),
+            roles,
+            user_type: USER_TYPE.to_string(),
+            password: Some(password.to_string()),
+            derived_key: None,
+            salt: None,
+            iterations: None,
+            password_scheme: None,
+            pbkdf2_prf: None,
+            other: Map::new(),
+        }
+    }
+
+    /// Sets a new password; the hashed password fields are cleared so `CouchDB` derives them again.
+    pub fn set_password(&mut self, password: &str) {
+        self.password = Some(password.to_string());
+        self.derived_key = None;
+        self.salt = None;
+        self.iterations = None;
+        self.password_scheme = None;
+        self.pbkdf2_prf = None;
+    }
+}
+
+/// Returns the document id for the user with the given name.
+/// ```
+/// use couch_rs::types::user::user_id;
+/// assert_eq!(user_id("jan"), "org.couchdb.user:jan");
+/// assert_eq!(user_id("org.couchdb.user:jan"), "org.couchdb.user:jan");
+/// ```
+#[must_use]
+pub fn user_id(name: &str) -> String {
+    if name.starts_with(USER_ID_PREFIX) {
+        name.to_string()
+    } else {
+        format!("{USER_ID_PREFIX}{name}")
+    }
+}
+
+impl TypedCouchDocument for CouchUser {
+    fn get_id(&self) -> Cow<'_, str> {
+        if self.id.is_empty() {
+            Cow::Owned(user_id(&self.name))
+        } else {
+            Cow::from(&self.id)
+        }
+    }
+
+    fn get_rev(&self) -> Cow<'_, str> {
+        Cow::from(&self.rev)
+    }
+
+    fn set_rev(&mut self, rev: &str) {
+        self.rev = rev.to_string();
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.id = id.to_string();
+    }
+
+    fn merge_ids(&mut self, other: &Self) {
+        self.set_id(&other.get_id());
+        self.set_rev(&other.get_rev());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn can_serialize_new_user() {
+        let user = CouchUser::new("jan", "apple", vec!["tenant".to_string()]);
+        assert_eq!(
+            serde_json::to_value(&user).unwrap(),
+            json!({
+                "_id": "org.couchdb.user:jan",
+                "name": "jan",
+                "roles": ["tenant"],
+                "type": "user",
+                "password": "apple"
+            })
+        );
+    }
+
+    #[test]
+    fn can_change_password_of_stored_user() {
+        let mut user: CouchUser = serde_json::from_value(json!({
+            "_id": "org.couchdb.user:jan",
+            "_rev": "1-abc",
+            "name": "jan",
+            "roles": [],
+            "type": "user",
+            "derived_key": "e579375db0e0c6a6fc79cd9e36a36859f71575c3",
+            "iterations": 10,
+            "password_scheme": "pbkdf2",
+            "salt": "1112283cf988a34f124200a050d308a1",
+            "email": "jan@example.com"
+        }))
+        .unwrap();
+        assert_eq!(user.other["email"], "jan@example.com");
+
+        user.set_password("orange");
+        let value = serde_json::to_value(&user).unwrap();
+        assert_eq!(value["password"], "orange");
+        assert!(value.get("derived_key").is_none());
+        assert_eq!(value["email"], "jan@example.com");
+    }
+
+    #[test]
+    fn derives_id_from_name() {
+        let mut user = CouchUser::new("jan", "apple", vec![]);
+        user.id = String::new();
+        assert_eq!(user.get_id(), "org.couchdb.user:jan");
+    }
+}
@@ -41,7 +41,7 @@ pub struct CouchViews {
 ///     reduce: None,
 /// };
 /// ```
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Clone)]
 pub struct CouchFunc {
     pub map: String,
     #[serde(skip_serializing_if = "Option::is_none")]
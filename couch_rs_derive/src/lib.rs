@@ -14,11 +14,11 @@ fn impl_derive_couch_doc(ast: &syn::DeriveInput) -> TokenStream {
 
     let gen = quote! {
         impl TypedCouchDocument for #name {
-            fn get_id(&self) -> couch_rs::Cow<str> {
+            fn get_id(&self) -> couch_rs::Cow<'_, str> {
                 couch_rs::Cow::from(&self._id)
             }
 
-            fn get_rev(&self) -> couch_rs::Cow<str> {
+            fn get_rev(&self) -> couch_rs::Cow<'_, str> {
                 couch_rs::Cow::from(&self._rev)
             }
 